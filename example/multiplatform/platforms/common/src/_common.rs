@@ -1,48 +1,28 @@
-mod arches;     pub use arches::Arches;
-mod config;     pub use config::Config;
+//! Helpers shared by the example tools, on top of [cargo_container_sdk].
+
 mod download;   pub use download::Download;
-pub mod exit;
-mod package;    pub use package::Package;
-mod state;      pub use state::State;
 
+pub use cargo_container_sdk::*;
+
+pub extern crate cargo_container_sdk;
 pub extern crate cargo_local_install;
 pub extern crate mmrbi;
+pub extern crate serde_json;
 
+use mmrbi::*;
 
+use std::path::Path;
 
-pub fn exec(tool: impl Tool, suffix: &str) {
-    let state = State::get(suffix);
-    let state = &state;
-    match state.command.as_str() {
-        "bench"     => tool.bench   (state),
-        "build"     => tool.build   (state),
-        "clean"     => tool.clean   (state),
-        "deploy"    => tool.deploy  (state),
-        "doc"       => tool.doc     (state),
-        "fetch"     => tool.fetch   (state),
-        "generate"  => tool.generate(state),
-        "run"       => tool.run     (state),
-        "package"   => tool.package (state),
-        "setup"     => tool.setup   (state),
-        "test"      => tool.test    (state),
-        "update"    => tool.update  (state),
-        _other      => exit::command_not_implemented(),
-    }
-}
 
 
-
-pub trait Tool {
-    fn bench    (&self, _state: &State) { exit::command_not_implemented() }
-    fn build    (&self, _state: &State) { exit::command_not_implemented() }
-    fn clean    (&self, _state: &State) { exit::command_not_implemented() }
-    fn deploy   (&self, _state: &State) { exit::command_not_implemented() }
-    fn doc      (&self, _state: &State) { exit::command_not_implemented() }
-    fn fetch    (&self, _state: &State) { exit::command_not_implemented() }
-    fn generate (&self, _state: &State) { exit::command_not_implemented() }
-    fn run      (&self, _state: &State) { exit::command_not_implemented() }
-    fn package  (&self, _state: &State) { exit::command_not_implemented() }
-    fn setup    (&self, _state: &State) { exit::command_not_implemented() }
-    fn test     (&self, _state: &State) { exit::command_not_implemented() }
-    fn update   (&self, _state: &State) { exit::command_not_implemented() }
+/// Delete a file or directory (recursively), if it exists - for implementing `Tool::clean`
+pub fn remove_all(path: impl AsRef<Path>) {
+    let path = path.as_ref();
+    let result = if path.is_dir() { std::fs::remove_dir_all(path) } else { std::fs::remove_file(path) };
+    match result {
+        Ok(()) => status!("Removed", "{}", path.display()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {},
+        Err(err) => fail!("unable to remove `{}`: {}", path.display(), err),
+    }
 }
+
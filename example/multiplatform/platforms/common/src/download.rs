@@ -1,5 +1,6 @@
 use sha2::Digest;
 
+use cargo_container_sdk::fail;
 use mmrbi::*;
 
 use std::fmt::Write as _ ;
@@ -19,15 +20,15 @@ impl Download {
         status!("Downloading", "{} ({})", self.name, self.url);
 
         let download = if let Some(path) = self.url.strip_prefix("file:///") {
-            std::fs::read(path).unwrap_or_else(|err| fatal!("unable to read {}: {}", path, err))
+            std::fs::read(path).unwrap_or_else(|err| fail!("unable to read {}: {}", path, err))
         } else {
             let ua = "github.com/MaulingMonkey/cargo-container/example/multiplatform/platforms/common";
             let req = ureq::get(self.url).set("User-Agent", ua).call();
             if !req.ok() {
-                fatal!("download failed with status {}: {}", req.status(), req.into_string().map_or_else(|_| String::from("???"), |s| s));
+                fail!("download failed with status {}: {}", req.status(), req.into_string().map_or_else(|_| String::from("???"), |s| s));
             }
             let mut v = Vec::new();
-            req.into_reader().read_to_end(&mut v).unwrap_or_else(|err| fatal!("error reading response: {}", err));
+            req.into_reader().read_to_end(&mut v).unwrap_or_else(|err| fail!("error reading response: {}", err));
             v
         };
 
@@ -39,7 +40,7 @@ impl Download {
         }
 
         if self.sha256 != hash {
-            fatal!("expected hash {}\r\nbut got hash {}", self.sha256, hash);
+            fail!("expected hash {}\r\nbut got hash {}", self.sha256, hash);
         }
 
         download
@@ -48,39 +49,39 @@ impl Download {
     pub fn download_gunzip(&self) -> impl AsRef<[u8]> {
         let mut o = Vec::new();
         libflate::gzip::Decoder::new(self.download().as_ref())
-            .unwrap_or_else(|err| fatal!("failed to gunzip: {}", err))
+            .unwrap_or_else(|err| fail!("failed to gunzip: {}", err))
             .read_to_end(&mut o)
-            .unwrap_or_else(|err| fatal!("failed to gunzip: {}", err));
+            .unwrap_or_else(|err| fail!("failed to gunzip: {}", err));
         o
     }
 
     pub fn download_gunzip_to(&self, to: impl AsRef<Path>, _unix_mode: u32) {
-        let mut o = std::fs::File::create(to.as_ref()).unwrap_or_else(|err| fatal!("failed to create {}: {}", to.as_ref().display(), err));
-        o.write_all(self.download_gunzip().as_ref()).unwrap_or_else(|err| fatal!("failed to write to {}: {}", to.as_ref().display(), err));
+        let mut o = std::fs::File::create(to.as_ref()).unwrap_or_else(|err| fail!("failed to create {}: {}", to.as_ref().display(), err));
+        o.write_all(self.download_gunzip().as_ref()).unwrap_or_else(|err| fail!("failed to write to {}: {}", to.as_ref().display(), err));
         #[cfg(unix)] {
             use std::os::unix::fs::PermissionsExt;
-            let mut perms = o.metadata().unwrap_or_else(|err| fatal!("failed to get permissions for {}: {}", to.as_ref().display(), err)).permissions();
+            let mut perms = o.metadata().unwrap_or_else(|err| fail!("failed to get permissions for {}: {}", to.as_ref().display(), err)).permissions();
             perms.set_mode(_unix_mode);
-            o.set_permissions(perms).unwrap_or_else(|err| fatal!("failed to set permissions for {}: {}", to.as_ref().display(), err));
+            o.set_permissions(perms).unwrap_or_else(|err| fail!("failed to set permissions for {}: {}", to.as_ref().display(), err));
         }
     }
 
     pub fn download_gunzip_untar_entry_to(&self, entry: impl AsRef<Path>, to: impl AsRef<Path>, _unix_mode: u32) {
         let mut tar = tar::Archive::new(std::io::Cursor::new(self.download_gunzip()));
-        for e in tar.entries().unwrap_or_else(|err| fatal!("failed to read tar entries: {}", err)) {
-            let mut e = e.unwrap_or_else(|err| fatal!("failed to read tar entry: {}", err));
-            let path = e.path().unwrap_or_else(|err| fatal!("failed to read tar entry path: {}", err));
+        for e in tar.entries().unwrap_or_else(|err| fail!("failed to read tar entries: {}", err)) {
+            let mut e = e.unwrap_or_else(|err| fail!("failed to read tar entry: {}", err));
+            let path = e.path().unwrap_or_else(|err| fail!("failed to read tar entry path: {}", err));
             if path == entry.as_ref() {
-                e.unpack(to.as_ref()).unwrap_or_else(|err| fatal!("failed to unpack {} to {}: {}", entry.as_ref().display(), to.as_ref().display(), err));
+                e.unpack(to.as_ref()).unwrap_or_else(|err| fail!("failed to unpack {} to {}: {}", entry.as_ref().display(), to.as_ref().display(), err));
                 #[cfg(unix)] {
                     use std::os::unix::fs::PermissionsExt;
-                    let mut perms = to.as_ref().metadata().unwrap_or_else(|err| fatal!("failed to get permissions for {}: {}", to.as_ref().display(), err)).permissions();
+                    let mut perms = to.as_ref().metadata().unwrap_or_else(|err| fail!("failed to get permissions for {}: {}", to.as_ref().display(), err)).permissions();
                     perms.set_mode(_unix_mode);
-                    std::fs::set_permissions(to.as_ref(), perms).unwrap_or_else(|err| fatal!("failed to set permissions for {}: {}", to.as_ref().display(), err));
+                    std::fs::set_permissions(to.as_ref(), perms).unwrap_or_else(|err| fail!("failed to set permissions for {}: {}", to.as_ref().display(), err));
                 }
                 return;
             }
         }
-        fatal!("unable to find {} in archive", entry.as_ref().display());
+        fail!("unable to find {} in archive", entry.as_ref().display());
     }
 }
@@ -7,21 +7,26 @@ use std::io::Write;
 
 
 
-fn main() { platform_common::exec(Tool, "console") }
+fn main() { platform_common::exec(Tool, "console").or_fail() }
 
 struct Tool;
 impl platform_common::Tool for Tool {
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+            .commands(&["generate", "build", "check", "clippy", "test", "run"])
+    }
+
     fn generate(&self, state: &State) {
         for package in state.packages.iter() {
             let out_dir = package.generated_path();
-            std::fs::create_dir_all(&out_dir).unwrap_or_else(|err| fatal!("unable to create `{}`: {}", out_dir.display(), err));
+            std::fs::create_dir_all(&out_dir).unwrap_or_else(|err| fail!("unable to create `{}`: {}", out_dir.display(), err));
 
             wimw(out_dir.join("main.rs"), |o|{
                 writeln!(o, "// AUTOGENERATED BY {}", env!("CARGO_PKG_NAME"))?;
                 writeln!(o)?;
                 writeln!(o, "fn main() {{ app::init(app_common::ConsoleDialogProvider) }}")?;
                 Ok(())
-            }).or_die();
+            }).or_fail();
 
             wimw(out_dir.join("Cargo.toml"), |o|{
                 writeln!(o, "# AUTOGENERATED BY {}", env!("CARGO_PKG_NAME"))?;
@@ -41,7 +46,7 @@ impl platform_common::Tool for Tool {
                 writeln!(o, "name            = {:?}", package.original_name())?;
                 writeln!(o, "path            = {:?}", "main.rs")?;
                 Ok(())
-            }).or_die();
+            }).or_fail();
         }
     }
 
@@ -49,13 +54,20 @@ impl platform_common::Tool for Tool {
         for config in state.configs.iter() {
             let mut cmd = Command::new("cargo");
             cmd.args(&["build"]);
-            match config.name() {
-                "debug"     => {},
-                "release"   => { cmd.arg("--release"); },
-                other       => fatal!("unexpected config: {:?}", other),
-            }
+            config.apply_to(&mut cmd);
             for package in state.packages.iter() { cmd.arg("-p"); cmd.arg(&package.generated_name()); }
-            cmd.status0().or_die();
+            cmd.status0().or_fail();
+        }
+    }
+
+
+    fn check(&self, state: &State) {
+        for config in state.configs.iter() {
+            let mut cmd = Command::new("cargo");
+            cmd.args(&["check"]);
+            config.apply_to(&mut cmd);
+            for package in state.packages.iter() { cmd.arg("-p"); cmd.arg(&package.generated_name()); }
+            cmd.status0().or_fail();
         }
     }
 
@@ -63,13 +75,18 @@ impl platform_common::Tool for Tool {
         for config in state.configs.iter() {
             let mut cmd = Command::new("cargo");
             cmd.args(&["test"]);
-            match config.name() {
-                "debug"     => {},
-                "release"   => { cmd.arg("--release"); },
-                other       => fatal!("unexpected config: {:?}", other),
-            }
+            config.apply_to(&mut cmd);
             for package in state.packages.iter() { cmd.arg("-p"); cmd.arg(&package.generated_name()); }
-            cmd.status0().or_die();
+            if !state.args.is_empty() { cmd.arg("--").args(&state.args); }
+            cmd.status0().or_fail();
         }
     }
+
+    fn run(&self, state: &State) {
+        let mut cmd = Command::new("cargo");
+        cmd.args(&["run", "-p", state.package().or_fail().generated_name()]);
+        state.config().or_fail().apply_to(&mut cmd);
+        cmd.arg("--").args(&state.args);
+        exit::status(cmd.status().unwrap_or_else(|err| fail!("unable to launch `cargo run`: {}", err)))
+    }
 }
@@ -57,7 +57,7 @@ fn supported(warn: bool) -> bool {
     let mut supported = true;
 
     // rustc version
-    let rustc = rustc::version().or_die();
+    let rustc = rustc::version().or_fail();
     let nightly = rustc.version.pre.iter().map(|s| s.to_string()).collect::<Vec<_>>() == ["nightly"];
     if !nightly {
         if warn { warning!("requires nightly rustc, on rustc {}", rustc.version); }
@@ -104,10 +104,22 @@ fn supported(warn: bool) -> bool {
 
 
 
-fn main() { platform_common::exec(Tool, "opendingux") }
+fn main() { platform_common::exec(Tool, "opendingux").or_fail() }
 
 struct Tool;
 impl platform_common::Tool for Tool {
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+            .commands(&["setup", "generate", "build", "check", "clippy", "package", "clean", "deploy"])
+            .arches(&["mipsel"])
+            .hosts(&["windows"]) // see `supported`
+    }
+
+    fn clippy(&self, _state: &State) {
+        // The default would lint for the host - xargo's custom mipsel sysroot doesn't extend to clippy
+        exit::platform_not_implemented()
+    }
+
     fn setup(&self, _state: &State) {
         if !supported(true) { return }
 
@@ -116,7 +128,7 @@ impl platform_common::Tool for Tool {
 
         if cfg!(target_os = "windows") {
             #[cfg(windows)] {
-                let wsl = wslapi::Library::new().unwrap_or_else(|err| fatal!(
+                let wsl = wslapi::Library::new().unwrap_or_else(|err| fail!(
                     "unable to check/install a distro for opendingux builds: WSL not available ({})!  You may need to install WSL, or restart if you recently did.",
                     err
                 ));
@@ -139,7 +151,7 @@ impl platform_common::Tool for Tool {
 
         for package in state.packages.iter() {
             let out_dir = package.generated_path();
-            std::fs::create_dir_all(&out_dir).unwrap_or_else(|err| fatal!("unable to create `{}`: {}", out_dir.display(), err));
+            std::fs::create_dir_all(&out_dir).unwrap_or_else(|err| fail!("unable to create `{}`: {}", out_dir.display(), err));
 
             // XXX: Allow merging Xargo.toml files from multiple sources
             wimw("Xargo.toml", |o|{
@@ -147,17 +159,17 @@ impl platform_common::Tool for Tool {
                 writeln!(o, "[target.mipsel-gcw0-linux-uclibc.dependencies.std]")?;
                 writeln!(o, "features = []")?;
                 Ok(())
-            }).or_die();
+            }).or_fail();
 
             // See https://github.com/MaulingMonkey/rust-opendingux-test/blob/master/mipsel-gcw0-linux-uclibc.json for some interesting notes
-            wimw("mipsel-gcw0-linux-uclibc.json", |o| write!(o, "{}", include_str!("mipsel-gcw0-linux-uclibc.json"))).or_die();
+            wimw("mipsel-gcw0-linux-uclibc.json", |o| write!(o, "{}", include_str!("mipsel-gcw0-linux-uclibc.json"))).or_fail();
 
             wimw(out_dir.join("main.rs"), |o|{
                 writeln!(o, "// AUTOGENERATED BY {}", env!("CARGO_PKG_NAME"))?;
                 writeln!(o)?;
                 writeln!(o, "fn main() {{ app::init(app_common::ConsoleDialogProvider) }}")?;
                 Ok(())
-            }).or_die();
+            }).or_fail();
 
             wimw(out_dir.join("Cargo.toml"), |o|{
                 writeln!(o, "# AUTOGENERATED BY {}", env!("CARGO_PKG_NAME"))?;
@@ -177,7 +189,7 @@ impl platform_common::Tool for Tool {
                 writeln!(o, "name            = {:?}", package.original_name())?;
                 writeln!(o, "path            = {:?}", "main.rs")?;
                 Ok(())
-            }).or_die();
+            }).or_fail();
         }
     }
 
@@ -187,13 +199,22 @@ impl platform_common::Tool for Tool {
         for config in state.configs.iter() {
             let mut cmd = Command::new("xargo");
             cmd.args(&["build", "--target=mipsel-gcw0-linux-uclibc"]);
-            match config.name() {
-                "debug"     => {},
-                "release"   => { cmd.arg("--release"); },
-                other       => fatal!("unexpected config: {:?}", other),
-            }
+            config.apply_to(&mut cmd);
+            for package in state.packages.iter() { cmd.arg("-p"); cmd.arg(&package.generated_name()); }
+            cmd.status0().or_fail();
+        }
+    }
+
+
+    fn check(&self, state: &State) {
+        if !supported(true) { return }
+
+        for config in state.configs.iter() {
+            let mut cmd = Command::new("xargo");
+            cmd.args(&["check", "--target=mipsel-gcw0-linux-uclibc"]);
+            config.apply_to(&mut cmd);
             for package in state.packages.iter() { cmd.arg("-p"); cmd.arg(&package.generated_name()); }
-            cmd.status0().or_die();
+            cmd.status0().or_fail();
         }
     }
 
@@ -205,17 +226,15 @@ impl platform_common::Tool for Tool {
             let pkg_opk = PathBuf::from(format!("target/opendingux/packages/{}.opk", package.original_name()));
             let _ = std::fs::remove_dir_all(&pkg_dir);
             let _ = std::fs::remove_file(&pkg_opk);
-            std::fs::create_dir_all(&pkg_dir).unwrap_or_else(|err| fatal!("unable to create {}: {}", pkg_dir.display(), err));
+            std::fs::create_dir_all(&pkg_dir).unwrap_or_else(|err| fail!("unable to create {}: {}", pkg_dir.display(), err));
             for config in state.configs.iter() {
-                let src_bin = PathBuf::from(format!("target/mipsel-gcw0-linux-uclibc/{}/{}", config.name(), package.original_name()));
+                let src_bin = PathBuf::from(format!("target/mipsel-gcw0-linux-uclibc/{}/{}", config.profile_dir(), package.original_name()));
                 let dst_bin = PathBuf::from(format!("target/opendingux/packages/{}/app.{}", package.original_name(), config.name()));
-                std::fs::copy(&src_bin, &dst_bin).unwrap_or_else(|err| fatal!("unable to copy {} to {}: {}", src_bin.display(), dst_bin.display(), err));
+                std::fs::copy(&src_bin, &dst_bin).unwrap_or_else(|err| fail!("unable to copy {} to {}: {}", src_bin.display(), dst_bin.display(), err));
                 wimw(format!("target/opendingux/packages/{}/{}.all.desktop", package.original_name(), config.name()), |o|{
-                    let name = package.original_name();
-                    // cargo-container doesn't currently pass this information to package commands
-                    //let desc = package.description();
-                    //let desc = if desc.is_empty() { name } else { desc.as_str() };
-                    let desc = name;
+                    let name = package.display_name();
+                    let desc = package.description();
+                    let desc = if desc.is_empty() { name } else { desc };
                     writeln!(o, "[Desktop Entry]")?;
                     writeln!(o, "Type=Application")?;
                     writeln!(o, "Version=1.0")?;
@@ -225,14 +244,21 @@ impl platform_common::Tool for Tool {
                         writeln!(o, "Name={name} ({config})", name=name, config=config.name())?;
                     }
                     writeln!(o, "Comment={desc}", desc=desc)?;
-                    writeln!(o, "Categories=rust;")?;
+                    match package.category() {
+                        Some(category)  => writeln!(o, "Categories={}{};rust;", category[..1].to_uppercase(), &category[1..])?,
+                        None            => writeln!(o, "Categories=rust;")?,
+                    }
                     writeln!(o, "Icon=icon")?;
-                    writeln!(o, "Terminal=true")?; // XXX
+                    writeln!(o, "Terminal={}", package.terminal().unwrap_or(true))?;
                     writeln!(o, "Exec=app.{config}", config=config.name())?;
                     Ok(())
-                }).or_die();
+                }).or_fail();
+            }
+            let icon = format!("target/opendingux/packages/{}/icon.png", package.original_name());
+            match package.icon() {
+                Some(src)   => { std::fs::copy(src, &icon).unwrap_or_else(|err| fail!("unable to copy {} to {}: {}", src.display(), icon, err)); },
+                None        => { wimw(&icon, |o| o.write_all(include_bytes!("placeholder-icon.png"))).or_fail(); },
             }
-            wimw(format!("target/opendingux/packages/{}/icon.png", package.original_name()), |o| o.write_all(include_bytes!("placeholder-icon.png"))).or_die();
             // TODO: filter to heck and back?
             Command::new("wsl")
                 .arg("-u").arg("opendingux")
@@ -242,7 +268,14 @@ impl platform_common::Tool for Tool {
                 .arg(&pkg_opk)
                 .arg("-comp").arg("gzip")
                 .arg("-noappend")
-                .status0().or_die();
+                .status0().or_fail();
+        }
+    }
+
+    fn clean(&self, state: &State) {
+        for package in state.packages.iter() {
+            remove_all(format!("target/opendingux/packages/{}", package.original_name()));
+            remove_all(format!("target/opendingux/packages/{}.opk", package.original_name()));
         }
     }
 
@@ -263,7 +296,7 @@ impl platform_common::Tool for Tool {
                 //  3.  You're not actually checking the new fingerprint anyways.
                 .arg(&src_opk)
                 .arg(format!("{user}@{ip}:{path}", user=dst_user, ip=dst_ip, path=dst_opk))
-                .status0().or_die();
+                .status0().or_fail();
         }
     }
 }
@@ -275,5 +308,5 @@ fn install_xargo() {
         "--locked",
         "--version",
         &format!("^{}", XARGO_VERSION),
-    ].into_iter()).or_die();
+    ].into_iter()).or_fail();
 }
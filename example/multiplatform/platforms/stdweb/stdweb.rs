@@ -40,7 +40,7 @@ pub const CARGO_WEB_GZS : &'static [CargoWebGz] = &[
 
 
 
-fn main() { platform_common::exec(Tool, "stdweb") }
+fn main() { platform_common::exec(Tool, "stdweb").or_fail() }
 
 fn cargo_web() -> Command {
     let cmd = if CARGO_WEB_GZS.iter().any(|wpt| wpt.cond) { "cargo-web-0.6.26" } else { "cargo-web" };
@@ -50,9 +50,17 @@ fn cargo_web() -> Command {
 
 struct Tool;
 impl platform_common::Tool for Tool {
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+            .commands(&["setup", "generate", "build", "check", "clean", "clippy", "test", "run"])
+            .arches(&["wasm32"])
+    }
+
+    fn targets(&self, _state: &State) -> Vec<Option<String>> { vec![Some("wasm32-unknown-unknown".into())] }
+
     fn setup(&self, _state: &State) {
-        let rustup = mmrbi::Rustup::default().or_die();
-        let toolchain = rustup.toolchains().active().ok_or("no active rustup toolchain").or_die();
+        let rustup = mmrbi::Rustup::default().or_fail();
+        let toolchain = rustup.toolchains().active().ok_or("no active rustup toolchain").or_fail();
         toolchain.targets().add("wasm32-unknown-unknown").unwrap_or_else(|err| warning!("{}", err));
 
         let mut any = false;
@@ -72,12 +80,12 @@ impl platform_common::Tool for Tool {
                 "--locked",
                 "--version",
                 &format!("^{}", CARGO_WEB_VERSION),
-            ].into_iter()).or_die();
+            ].into_iter()).or_fail();
         }
 
         if cfg!(target_os = "linux") {
             if !Path::new("/usr/include/openssl/ssl.h").exists() {
-                println!("cargo-container:apt-get-install=libssl-dev");
+                directive::apt_get_install("libssl-dev").or_fail();
             }
         }
     }
@@ -85,14 +93,14 @@ impl platform_common::Tool for Tool {
     fn generate(&self, state: &State) {
         for package in state.packages.iter() {
             let out_dir = package.generated_path();
-            std::fs::create_dir_all(&out_dir).unwrap_or_else(|err| fatal!("unable to create `{}`: {}", out_dir.display(), err));
+            std::fs::create_dir_all(&out_dir).unwrap_or_else(|err| fail!("unable to create `{}`: {}", out_dir.display(), err));
 
             wimw(out_dir.join("main.rs"), |o|{
                 writeln!(o, "// AUTOGENERATED BY {}", env!("CARGO_PKG_NAME"))?;
                 writeln!(o)?;
                 writeln!(o, "fn main() {{ app::init(app_common::StdwebDialogProvider) }}")?;
                 Ok(())
-            }).or_die();
+            }).or_fail();
 
             wimw(out_dir.join("Cargo.toml"), |o|{
                 writeln!(o, "# AUTOGENERATED BY {}", env!("CARGO_PKG_NAME"))?;
@@ -112,7 +120,7 @@ impl platform_common::Tool for Tool {
                 writeln!(o, "name            = {:?}", package.generated_name())?;
                 writeln!(o, "path            = {:?}", "main.rs")?;
                 Ok(())
-            }).or_die();
+            }).or_fail();
         }
     }
 
@@ -122,32 +130,54 @@ impl platform_common::Tool for Tool {
                 let mut cmd = cargo_web();
                 cmd.current_dir(package.generated_path());
                 cmd.args(&["build"]);
-                match config.name() {
-                    "debug"     => {},
+                match config.profile() {
+                    "dev"       => {},
                     "release"   => { cmd.arg("--release"); },
-                    other       => fatal!("unexpected config: {:?}", other),
+                    other       => fail!("cargo web only supports the `dev` and `release` profiles, not {:?}", other),
                 }
-                cmd.io0(filter_stdout, filter_stderr).or_die();
+                config.apply_extras_to(&mut cmd);
+                cmd.io0(filter_stdout, filter_stderr).or_fail();
 
-                wimw(format!("target/wasm32-unknown-unknown/{config}/{package}.html", config=config.name(), package=package.generated_name()), |o|{
+                wimw(format!("target/wasm32-unknown-unknown/{config}/{package}.html", config=config.profile_dir(), package=package.generated_name()), |o|{
                     writeln!(o, "<!DOCTYPE html>")?;
                     writeln!(o, "<html lang=\"en\"><head>")?;
                     writeln!(o, "    <meta charset=\"UTF-8\">")?;
                     writeln!(o, "    <meta http-equiv=\"X-UA-Compatible\" content=\"IE=edge\">")?;
-                    writeln!(o, "    <title>{}</title>", package.original_name())?;
+                    writeln!(o, "    <title>{}</title>", package.display_name())?;
                     writeln!(o, "</head><body>")?;
                     writeln!(o, "    <script src=\"../stdweb-hacks.js\"></script>")?;
                     writeln!(o, "    <script src=\"{}.js\"></script>", package.generated_name())?;
                     writeln!(o, "</body></html>")?;
                     Ok(())
-                }).or_die();
+                }).or_fail();
             }
         }
-        wimw("target/wasm32-unknown-unknown/stdweb-hacks.js", |o| write!(o, "{}", include_str!("stdweb-hacks.js"))).or_die();
+        wimw("target/wasm32-unknown-unknown/stdweb-hacks.js", |o| write!(o, "{}", include_str!("stdweb-hacks.js"))).or_fail();
+    }
+
+
+    fn check(&self, state: &State) {
+        // `cargo web` has no `check` - cargo does just fine for wasm32
+        for config in state.configs.iter() {
+            let mut cmd = Command::new("cargo");
+            cmd.args(&["check", "--target", "wasm32-unknown-unknown"]);
+            config.apply_to(&mut cmd);
+            for package in state.packages.iter() { cmd.arg("-p"); cmd.arg(&package.generated_name()); }
+            cmd.status0().or_fail();
+        }
+    }
+
+    fn clean(&self, state: &State) {
+        for config in state.configs.iter() {
+            for package in state.packages.iter() {
+                remove_all(format!("target/wasm32-unknown-unknown/{config}/{package}.html", config=config.profile_dir(), package=package.generated_name()));
+            }
+        }
+        remove_all("target/wasm32-unknown-unknown/stdweb-hacks.js");
     }
 
     fn test(&self, state: &State) {
-        if rustc::version().or_die().is_after(1, 43, 0) { // last known good rustc version
+        if rustc::version().or_fail().is_after(1, 43, 0) { // last known good rustc version
             warning!("skipping tests - `cargo web test` is broken on rustc 1.44+ (see https://github.com/koute/cargo-web/issues/243 for details)");
             return;
         }
@@ -179,15 +209,29 @@ impl platform_common::Tool for Tool {
                 cmd.current_dir(package.generated_path());
                 cmd.args(&["test"]);
                 cmd.env("PATH", &path);
-                match config.name() {
-                    "debug"     => {},
+                match config.profile() {
+                    "dev"       => {},
                     "release"   => { cmd.arg("--release"); },
-                    other       => fatal!("unexpected config: {:?}", other),
+                    other       => fail!("cargo web only supports the `dev` and `release` profiles, not {:?}", other),
                 }
-                cmd.io0(filter_stdout, filter_stderr).or_die();
+                config.apply_extras_to(&mut cmd);
+                cmd.io0(filter_stdout, filter_stderr).or_fail();
             }
         }
     }
+
+    fn run(&self, state: &State) {
+        let mut cmd = cargo_web();
+        cmd.current_dir(state.package().or_fail().generated_path());
+        cmd.args(&["start", "--open"]);
+        match state.config().or_fail().profile() {
+            "dev"       => {},
+            "release"   => { cmd.arg("--release"); },
+            other       => fail!("cargo web only supports the `dev` and `release` profiles, not {:?}", other),
+        }
+        state.config().or_fail().apply_extras_to(&mut cmd);
+        exit::status(cmd.status().unwrap_or_else(|err| fail!("unable to launch `cargo web start`: {}", err)))
+    }
 }
 
 fn filter_stderr(line: &str) {
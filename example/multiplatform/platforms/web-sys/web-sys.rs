@@ -51,7 +51,7 @@ pub const WASM_PACK_TARS : &'static [WasmPackTarGz] = &[
 trait PackageExt                { fn generated_target(&self) -> String; }
 impl  PackageExt for Package    { fn generated_target(&self) -> String { self.generated_name().replace("-", "_") } }
 
-fn main() { platform_common::exec(Tool, "web-sys") }
+fn main() { platform_common::exec(Tool, "web-sys").or_fail() }
 
 fn wasm_pack() -> Command {
     let cmd = if WASM_PACK_TARS.iter().any(|wpt| wpt.cond) { "wasm-pack-0.9.1" } else { "wasm-pack" };
@@ -61,9 +61,17 @@ fn wasm_pack() -> Command {
 
 struct Tool;
 impl platform_common::Tool for Tool {
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+            .commands(&["setup", "generate", "build", "check", "clean", "clippy", "test", "run"])
+            .arches(&["wasm32"])
+    }
+
+    fn targets(&self, _state: &State) -> Vec<Option<String>> { vec![Some("wasm32-unknown-unknown".into())] }
+
     fn setup(&self, _state: &State) {
-        let rustup = mmrbi::Rustup::default().unwrap_or_else(|err| fatal!("unable to find rustup: {}", err));
-        let toolchain = rustup.toolchains().active().unwrap_or_else(|| fatal!("no active rustup toolchain"));
+        let rustup = mmrbi::Rustup::default().unwrap_or_else(|err| fail!("unable to find rustup: {}", err));
+        let toolchain = rustup.toolchains().active().unwrap_or_else(|| fail!("no active rustup toolchain"));
         toolchain.targets().add("wasm32-unknown-unknown").unwrap_or_else(|err| warning!("{}", err));
 
         let mut any = false;
@@ -83,14 +91,14 @@ impl platform_common::Tool for Tool {
                 "--locked",
                 "--version",
                 &format!("^{}", WASM_PACK_VERSION),
-            ].into_iter()).unwrap_or_else(|err| fatal!("cargo local-install wasm-pack failed: {}", err));
+            ].into_iter()).unwrap_or_else(|err| fail!("cargo local-install wasm-pack failed: {}", err));
         }
     }
 
     fn generate(&self, state: &State) {
         for package in state.packages.iter() {
             let out_dir = package.generated_path();
-            std::fs::create_dir_all(&out_dir).unwrap_or_else(|err| fatal!("unable to create `{}`: {}", out_dir.display(), err));
+            std::fs::create_dir_all(&out_dir).unwrap_or_else(|err| fail!("unable to create `{}`: {}", out_dir.display(), err));
 
             wimw(out_dir.join("lib.rs"), |o|{
                 writeln!(o, "// AUTOGENERATED BY {}", env!("CARGO_PKG_NAME"))?;
@@ -103,7 +111,7 @@ impl platform_common::Tool for Tool {
                 writeln!(o, "    app::init(app_common::WebSysDialogProvider);")?;
                 writeln!(o, "}}")?;
                 Ok(())
-            }).or_die();
+            }).or_fail();
 
             wimw(out_dir.join("Cargo.toml"), |o|{
                 writeln!(o, "# AUTOGENERATED BY {}", env!("CARGO_PKG_NAME"))?;
@@ -124,7 +132,7 @@ impl platform_common::Tool for Tool {
                 writeln!(o, "name            = {:?}", package.generated_name().replace("-", "_"))?;
                 writeln!(o, "path            = {:?}", "lib.rs")?;
                 Ok(())
-            }).or_die();
+            }).or_fail();
         }
     }
 
@@ -135,19 +143,21 @@ impl platform_common::Tool for Tool {
                 cmd.current_dir(package.generated_path());
                 let pkg_dir = format!("../../../../target/wasm32-unknown-unknown/{config}/{package}", config=config.name(), package=package.generated_name());
                 cmd.args(&["build", "--no-typescript", "--target", "no-modules", "--out-dir", &pkg_dir]);
-                match config.name() {
-                    "debug"     => { cmd.arg("--dev"); },
+                match config.profile() {
+                    "dev"       => { cmd.arg("--dev"); },
                     "release"   => { cmd.arg("--profiling"); }, // release + debuginfo
-                    other       => fatal!("unexpected config: {:?}", other),
+                    other       => fail!("wasm-pack only supports the `dev` and `release` profiles, not {:?}", other),
                 }
-                cmd.io0(filter_stdout, filter_stderr).or_die();
+                cmd.arg("--");
+                config.apply_extras_to(&mut cmd);
+                cmd.io0(filter_stdout, filter_stderr).or_fail();
 
                 wimw(format!("target/wasm32-unknown-unknown/{config}/{package}/index.html", config=config.name(), package=package.generated_name()), |o|{
                     writeln!(o, "<!DOCTYPE html>")?;
                     writeln!(o, "<html lang=\"en\"><head>")?;
                     writeln!(o, "    <meta charset=\"UTF-8\">")?;
                     writeln!(o, "    <meta http-equiv=\"X-UA-Compatible\" content=\"IE=edge\">")?;
-                    writeln!(o, "    <title>{}</title>", package.original_name())?;
+                    writeln!(o, "    <title>{}</title>", package.display_name())?;
                     writeln!(o, "</head><body>")?;
                     // what did these do again? IE pollyfills perhaps?
                     //writeln!(o, "    <script src="https://unpkg.com/text-encoding@0.6.4/lib/encoding-indexes.js"></script>")?;
@@ -168,7 +178,27 @@ impl platform_common::Tool for Tool {
                     writeln!(o, "    </script>")?;
                     writeln!(o, "</body></html>")?;
                     Ok(())
-                }).or_die();
+                }).or_fail();
+            }
+        }
+    }
+
+
+    fn check(&self, state: &State) {
+        // wasm-pack has no `check` - cargo does just fine for wasm32
+        for config in state.configs.iter() {
+            let mut cmd = Command::new("cargo");
+            cmd.args(&["check", "--target", "wasm32-unknown-unknown"]);
+            config.apply_to(&mut cmd);
+            for package in state.packages.iter() { cmd.arg("-p"); cmd.arg(&package.generated_name()); }
+            cmd.status0().or_fail();
+        }
+    }
+
+    fn clean(&self, state: &State) {
+        for config in state.configs.iter() {
+            for package in state.packages.iter() {
+                remove_all(format!("target/wasm32-unknown-unknown/{config}/{package}", config=config.name(), package=package.generated_name()));
             }
         }
     }
@@ -186,15 +216,33 @@ impl platform_common::Tool for Tool {
                 //cmd.arg("--firefox");
                 //cmd.arg("--safari");
 
-                match config.name() {
-                    "debug"     => {},
+                match config.profile() {
+                    "dev"       => {},
                     "release"   => { cmd.arg("--release"); },
-                    other       => fatal!("unexpected config: {:?}", other),
+                    other       => fail!("wasm-pack only supports the `dev` and `release` profiles, not {:?}", other),
                 }
-                cmd.io0(filter_stdout, filter_stderr).or_die();
+                cmd.arg("--");
+                config.apply_extras_to(&mut cmd);
+                cmd.io0(filter_stdout, filter_stderr).or_fail();
             }
         }
     }
+
+    fn run(&self, state: &State) {
+        self.build(state);
+        let index = format!("target/wasm32-unknown-unknown/{config}/{package}/index.html", config=state.config().or_fail().name(), package=state.package().or_fail().generated_name());
+        let mut cmd = if cfg!(windows) {
+            let mut cmd = Command::new("cmd");
+            cmd.args(&["/C", "start", ""]);
+            cmd
+        } else if cfg!(target_os = "macos") {
+            Command::new("open")
+        } else {
+            Command::new("xdg-open")
+        };
+        cmd.arg(&index);
+        cmd.status0().unwrap_or_else(|err| fail!("unable to open `{}` in a browser: {}", index, err));
+    }
 }
 
 fn filter_stderr(line: &str) {
@@ -29,15 +29,21 @@ use std::process::Stdio;
 // sudo ln -s /usr/bin/x86_64-w64-mingw32-windres /usr/bin/windres ?
 
 fn main() {
-    platform_common::exec(Tool, "windows")
+    platform_common::exec(Tool, "windows").or_fail()
 }
 
 struct Tool;
 
-impl Tool {
-    fn targets(&self, state: &State) -> BTreeSet<Option<&'static str>> {
-        let rustup = mmrbi::Rustup::default().unwrap_or_else(|err| fatal!("unable to find rustup: {}", err));
-        let toolchain = rustup.toolchains().active().unwrap_or_else(|| fatal!("no active rustup toolchain"));
+impl platform_common::Tool for Tool {
+    fn capabilities(&self) -> Capabilities {
+        Capabilities::default()
+            .commands(&["setup", "generate", "build", "check", "clippy", "test", "run"])
+            .arches(&["aarch64", "x86_64", "x86", "i686", "i586"])
+    }
+
+    fn targets(&self, state: &State) -> Vec<Option<String>> {
+        let rustup = mmrbi::Rustup::default().unwrap_or_else(|err| fail!("unable to find rustup: {}", err));
+        let toolchain = rustup.toolchains().active().unwrap_or_else(|| fail!("no active rustup toolchain"));
 
         let aarch64 = toolchain.as_str().contains("-aarch64-");
         let x86_64  = toolchain.as_str().contains("-x86_64-");
@@ -63,14 +69,12 @@ impl Tool {
         if targets.is_empty() {
             targets.insert(None);
         }
-        targets
+        targets.into_iter().map(|t| t.map(String::from)).collect()
     }
-}
 
-impl platform_common::Tool for Tool {
     fn setup(&self, state: &State) {
-        let rustup = mmrbi::Rustup::default().unwrap_or_else(|err| fatal!("unable to find rustup: {}", err));
-        let toolchain = rustup.toolchains().active().unwrap_or_else(|| fatal!("no active rustup toolchain"));
+        let rustup = mmrbi::Rustup::default().unwrap_or_else(|err| fail!("unable to find rustup: {}", err));
+        let toolchain = rustup.toolchains().active().unwrap_or_else(|| fail!("no active rustup toolchain"));
 
         let aarch64 = toolchain.as_str().contains("-aarch64-");
         let x86_64  = toolchain.as_str().contains("-x86_64-");
@@ -78,25 +82,25 @@ impl platform_common::Tool for Tool {
         let i586    = toolchain.as_str().contains("-i586-");
 
         if toolchain.as_str().ends_with("-msvc") {
-            if state.arches.contains("aarch64"  ).unwrap_or(aarch64       ) { toolchain.targets().add("aarch64-pc-windows-msvc"  ).or_die() }
-            if state.arches.contains("x86_64"   ).unwrap_or(x86_64        ) { toolchain.targets().add("x86_64-pc-windows-msvc"   ).or_die() }
-            if state.arches.contains("x86"      ).unwrap_or(false         ) { toolchain.targets().add("i686-pc-windows-msvc"     ).or_die() }
-            if state.arches.contains("i686"     ).unwrap_or(i686 || x86_64) { toolchain.targets().add("i686-pc-windows-msvc"     ).or_die() }
-            if state.arches.contains("i586"     ).unwrap_or(i586          ) { toolchain.targets().add("i586-pc-windows-msvc"     ).or_die() }
+            if state.arches.contains("aarch64"  ).unwrap_or(aarch64       ) { toolchain.targets().add("aarch64-pc-windows-msvc"  ).or_fail() }
+            if state.arches.contains("x86_64"   ).unwrap_or(x86_64        ) { toolchain.targets().add("x86_64-pc-windows-msvc"   ).or_fail() }
+            if state.arches.contains("x86"      ).unwrap_or(false         ) { toolchain.targets().add("i686-pc-windows-msvc"     ).or_fail() }
+            if state.arches.contains("i686"     ).unwrap_or(i686 || x86_64) { toolchain.targets().add("i686-pc-windows-msvc"     ).or_fail() }
+            if state.arches.contains("i586"     ).unwrap_or(i586          ) { toolchain.targets().add("i586-pc-windows-msvc"     ).or_fail() }
         } else if toolchain.as_str().ends_with("-gnu") {
-            if state.arches.contains("aarch64"  ).unwrap_or(aarch64       ) { toolchain.targets().add("aarch64-pc-windows-gnu"  ).or_die() }
-            if state.arches.contains("x86_64"   ).unwrap_or(x86_64        ) { toolchain.targets().add("x86_64-pc-windows-gnu"   ).or_die() }
-            if state.arches.contains("x86"      ).unwrap_or(false         ) { toolchain.targets().add("i686-pc-windows-gnu"     ).or_die() }
-            if state.arches.contains("i686"     ).unwrap_or(i686 || x86_64) { toolchain.targets().add("i686-pc-windows-gnu"     ).or_die() }
-            if state.arches.contains("i586"     ).unwrap_or(i586          ) { toolchain.targets().add("i586-pc-windows-gnu"     ).or_die() }
+            if state.arches.contains("aarch64"  ).unwrap_or(aarch64       ) { toolchain.targets().add("aarch64-pc-windows-gnu"  ).or_fail() }
+            if state.arches.contains("x86_64"   ).unwrap_or(x86_64        ) { toolchain.targets().add("x86_64-pc-windows-gnu"   ).or_fail() }
+            if state.arches.contains("x86"      ).unwrap_or(false         ) { toolchain.targets().add("i686-pc-windows-gnu"     ).or_fail() }
+            if state.arches.contains("i686"     ).unwrap_or(i686 || x86_64) { toolchain.targets().add("i686-pc-windows-gnu"     ).or_fail() }
+            if state.arches.contains("i586"     ).unwrap_or(i586          ) { toolchain.targets().add("i586-pc-windows-gnu"     ).or_fail() }
 
             if cfg!(target_os = "linux") {
                 if x86_64 && Command::new("x86_64-w64-mingw32-gcc").arg("--version").stdout(|| Stdio::null()).status0().is_err() {
-                    println!("cargo-container:apt-get-install=gcc-mingw-w64-x86-64");
+                    directive::apt_get_install("gcc-mingw-w64-x86-64").or_fail();
                 }
                 if (i586 || i686) && Command::new("i686-w64-mingw32-gcc").arg("--version").stdout(|| Stdio::null()).status0().is_err() {
                     // Not sure if this works for i586?
-                    println!("cargo-container:apt-get-install=gcc-mingw-w64-i686");
+                    directive::apt_get_install("gcc-mingw-w64-i686").or_fail();
                 }
             }
         } else {
@@ -105,9 +109,16 @@ impl platform_common::Tool for Tool {
     }
 
     fn generate(&self, state: &State) {
+        if wine() {
+            // Let `run` / `test` launch the cross compiled executables (see `can_run`)
+            for target in self.targets(state).into_iter().flatten() {
+                directive::cargo_config(serde_json::json!({ "target": { target: { "runner": "wine" } } })).or_fail();
+            }
+        }
+
         for package in state.packages.iter() {
             let out_dir = package.generated_path();
-            std::fs::create_dir_all(&out_dir).unwrap_or_else(|err| fatal!("unable to create `{}`: {}", out_dir.display(), err));
+            std::fs::create_dir_all(&out_dir).unwrap_or_else(|err| fail!("unable to create `{}`: {}", out_dir.display(), err));
 
             wimw(out_dir.join("main.rs"), |o|{
                 writeln!(o, "// AUTOGENERATED BY {}", env!("CARGO_PKG_NAME"))?;
@@ -115,7 +126,7 @@ impl platform_common::Tool for Tool {
                 writeln!(o)?;
                 writeln!(o, "fn main() {{ app::init(app_common::WindowsDialogProvider) }}")?;
                 Ok(())
-            }).or_die();
+            }).or_fail();
 
             wimw(out_dir.join("build.rs"), |o|{
                 writeln!(o, "// AUTOGENERATED BY {}", env!("CARGO_PKG_NAME"))?;
@@ -124,7 +135,7 @@ impl platform_common::Tool for Tool {
                 writeln!(o, "    winres::WindowsResource::new().compile().unwrap_or_else(|err| println!(\"cargo:warning=winres failed for {{}}: {{}}\", env!(\"CARGO_PKG_NAME\"), err));")?;
                 writeln!(o, "}}")?;
                 Ok(())
-            }).or_die();
+            }).or_fail();
 
             wimw(out_dir.join("Cargo.toml"), |o|{
                 writeln!(o, "# AUTOGENERATED BY {}", env!("CARGO_PKG_NAME"))?;
@@ -148,52 +159,85 @@ impl platform_common::Tool for Tool {
                 writeln!(o, "name            = {:?}", package.original_name())?;
                 writeln!(o, "path            = {:?}", "main.rs")?;
                 Ok(())
-            }).or_die();
+            }).or_fail();
         }
     }
 
     fn build(&self, state: &State) {
         let targets = self.targets(state);
         for config in state.configs.iter() {
-            for target in targets.iter().copied() {
+            for target in targets.iter() {
                 let mut cmd = Command::new("cargo");
                 cmd.args(&["build"]);
                 if let Some(target) = target {
                     cmd.arg("--target").arg(target);
                 }
-                match config.name() {
-                    "debug"     => {},
-                    "release"   => { cmd.arg("--release"); },
-                    other       => fatal!("unexpected config: {:?}", other),
+                config.apply_to(&mut cmd);
+                for package in state.packages.iter() { cmd.arg("-p"); cmd.arg(&package.generated_name()); }
+                cmd.status0().or_fail()
+            }
+        }
+    }
+
+
+    fn check(&self, state: &State) {
+        let targets = self.targets(state);
+        for config in state.configs.iter() {
+            for target in targets.iter() {
+                let mut cmd = Command::new("cargo");
+                cmd.args(&["check"]);
+                if let Some(target) = target {
+                    cmd.arg("--target").arg(target);
                 }
+                config.apply_to(&mut cmd);
                 for package in state.packages.iter() { cmd.arg("-p"); cmd.arg(&package.generated_name()); }
-                cmd.status0().or_die()
+                cmd.status0().or_fail()
             }
         }
     }
 
     fn test(&self, state: &State) {
-        if !cfg!(windows) {
-            warning!("skipping tests - `cargo test --target *-pc-windows-*` requires windows");
+        if !can_run() {
+            warning!("skipping tests - `cargo test --target *-pc-windows-*` requires windows (or wine on linux)");
             return;
         }
 
         let targets = self.targets(state);
         for config in state.configs.iter() {
-            for target in targets.iter().copied() {
+            for target in targets.iter() {
                 let mut cmd = Command::new("cargo");
                 cmd.args(&["test"]);
                 if let Some(target) = target {
                     cmd.arg("--target").arg(target);
                 }
-                match config.name() {
-                    "debug"     => {},
-                    "release"   => { cmd.arg("--release"); },
-                    other       => fatal!("unexpected config: {:?}", other),
-                }
+                config.apply_to(&mut cmd);
                 for package in state.packages.iter() { cmd.arg("-p"); cmd.arg(&package.generated_name()); }
-                cmd.status0().or_die()
+                if !state.args.is_empty() { cmd.arg("--").args(&state.args); }
+                cmd.status0().or_fail()
             }
         }
     }
+
+    fn run(&self, state: &State) {
+        if !can_run() {
+            warning!("unable to run - `cargo run --target *-pc-windows-*` requires windows (or wine on linux)");
+            exit::platform_not_implemented();
+        }
+
+        let mut cmd = Command::new("cargo");
+        cmd.args(&["run", "-p", state.package().or_fail().generated_name()]);
+        if let Some(target) = self.targets(state).into_iter().next().flatten() {
+            cmd.arg("--target").arg(target);
+        }
+        state.config().or_fail().apply_to(&mut cmd);
+        cmd.arg("--").args(&state.args);
+        exit::status(cmd.status().unwrap_or_else(|err| fail!("unable to launch `cargo run`: {}", err)))
+    }
+}
+
+/// Can windows executables be launched?  On linux, `generate` configures `wine` as their cargo `runner`.
+fn can_run() -> bool { cfg!(windows) || wine() }
+
+fn wine() -> bool {
+    cfg!(target_os = "linux") && std::env::var_os("PATH").is_some_and(|path| std::env::split_paths(&path).any(|dir| dir.join("wine").is_file()))
 }
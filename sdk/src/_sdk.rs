@@ -0,0 +1,147 @@
+//! Write tools for [cargo-container](https://github.com/MaulingMonkey/cargo-container).
+//!
+//! ```no_run
+//! use cargo_container_sdk::*;
+//!
+//! struct Console;
+//!
+//! impl Tool for Console {
+//!     fn build(&self, state: &State) {
+//!         for config in state.configs.iter() {
+//!             for package in state.packages.iter() {
+//!                 println!("building {} ({})", package.generated_name(), config.name());
+//!             }
+//!         }
+//!     }
+//! }
+//!
+//! fn main() { exec(Console, "console").or_fail() }
+//! ```
+
+#![allow(clippy::unnecessary_map_or)] // `Option::is_none_or` requires rust 1.82
+
+mod arches;     pub use arches::Arches;
+mod config;     pub use config::Config;
+pub mod directive;
+mod error;      pub use error::{Error, Result};
+pub mod exit;   pub use exit::OrFail;
+mod lint;
+mod package;    pub use package::Package;
+pub mod spec;   pub use spec::Capabilities;
+mod state;      pub use state::State;
+
+#[doc(hidden)] pub use mmrbi; // for `fail!`
+
+
+
+/// Read the [State] `cargo container` invoked the tool with, and dispatch to the appropriate [Tool] method.
+///
+/// The [spec::CAPABILITIES] command is answered with [Tool::capabilities].
+/// Fails if the tool wasn't invoked the way [spec] describes (e.g. outside of `cargo container`.)
+pub fn exec(tool: impl Tool, suffix: &str) -> Result<()> {
+    if std::env::var_os(spec::env::COMMAND).is_some_and(|c| c == spec::CAPABILITIES) {
+        println!("{}", capabilities(&tool, &State::capabilities()?).to_json());
+        return Ok(());
+    }
+
+    let state = State::from_env(suffix)?;
+    match DISPATCH.iter().find(|(command, _)| *command == state.command) {
+        Some((_, method))   => method(&tool, &state),
+        None                => exit::command_not_implemented(),
+    }
+    Ok(())
+}
+
+/// [Tool::capabilities], with [Capabilities::targets] filled in from [Tool::targets] (unless the tool already set them, or only builds for the host)
+fn capabilities(tool: &dyn Tool, state: &State) -> Capabilities {
+    let mut caps = tool.capabilities();
+    if caps.targets.is_none() {
+        let targets = tool.targets(state).into_iter().flatten().collect::<Vec<_>>();
+        if !targets.is_empty() { caps.targets = Some(targets) }
+    }
+    caps
+}
+
+/// The [Tool] method [exec] calls for each of [spec::COMMANDS]
+const DISPATCH : &[(&str, Method)] = &[
+    ("bench",       |tool, state| tool.bench   (state)),
+    ("build",       |tool, state| tool.build   (state)),
+    ("check",       |tool, state| tool.check   (state)),
+    ("clean",       |tool, state| tool.clean   (state)),
+    ("clippy",      |tool, state| tool.clippy  (state)),
+    ("deploy",      |tool, state| tool.deploy  (state)),
+    ("doc",         |tool, state| tool.doc     (state)),
+    ("fetch",       |tool, state| tool.fetch   (state)),
+    ("fuzz",        |tool, state| tool.fuzz    (state)),
+    ("generate",    |tool, state| tool.generate(state)),
+    ("package",     |tool, state| tool.package (state)),
+    ("run",         |tool, state| tool.run     (state)),
+    ("setup",       |tool, state| tool.setup   (state)),
+    ("test",        |tool, state| tool.test    (state)),
+    ("update",      |tool, state| tool.update  (state)),
+];
+
+type Method = fn(&dyn Tool, &State);
+
+
+
+/// A `cargo container` tool.  Unimplemented commands exit with [exit::COMMAND_NOT_IMPLEMENTED], except for [Tool::clippy].
+pub trait Tool {
+    /// What the tool supports, letting `cargo container` skip invocations that would do nothing.
+    /// Defaults to "unknown," which `cargo container` treats as supporting everything.
+    fn capabilities(&self) -> Capabilities { Capabilities::default() }
+
+    /// Target triples the generated packages build for (`None` for the host's), used by [Tool::clippy].  Defaults to the host's.
+    ///
+    /// This is also reported as [Capabilities::targets] (evaluated without packages or configs, and the default arches), so `cargo container`
+    /// can `cargo check` the generated packages for the right targets when the tool doesn't implement `check` itself.
+    fn targets(&self, _state: &State) -> Vec<Option<String>> { vec![None] }
+
+    fn bench    (&self, _state: &State) { exit::command_not_implemented() }
+    fn build    (&self, _state: &State) { exit::command_not_implemented() }
+    fn check    (&self, _state: &State) { exit::command_not_implemented() }
+    fn clean    (&self, _state: &State) { exit::command_not_implemented() }
+    fn deploy   (&self, _state: &State) { exit::command_not_implemented() }
+    fn doc      (&self, _state: &State) { exit::command_not_implemented() }
+    fn fetch    (&self, _state: &State) { exit::command_not_implemented() }
+    fn fuzz     (&self, _state: &State) { exit::command_not_implemented() }
+    fn generate (&self, _state: &State) { exit::command_not_implemented() }
+    fn run      (&self, _state: &State) { exit::command_not_implemented() }
+    fn package  (&self, _state: &State) { exit::command_not_implemented() }
+    fn setup    (&self, _state: &State) { exit::command_not_implemented() }
+    fn test     (&self, _state: &State) { exit::command_not_implemented() }
+    fn update   (&self, _state: &State) { exit::command_not_implemented() }
+
+    /// `cargo clippy` the generated packages for each of [Tool::targets], reporting lints back to `cargo container` as diagnostics.
+    fn clippy(&self, state: &State) {
+        match lint::clippy(state, &self.targets(state)).or_fail() {
+            exit::SUCCESS   => {},
+            code            => std::process::exit(code),
+        }
+    }
+}
+
+#[test] fn test_exec_handles_every_command() {
+    assert_eq!(DISPATCH.iter().map(|(command, _)| *command).collect::<Vec<_>>(), spec::COMMANDS);
+}
+
+#[test] fn test_capabilities_targets() {
+    struct Host;
+    impl Tool for Host {}
+
+    struct Wasm;
+    impl Tool for Wasm {
+        fn targets(&self, _state: &State) -> Vec<Option<String>> { vec![Some("wasm32-unknown-unknown".into())] }
+    }
+
+    struct Explicit;
+    impl Tool for Explicit {
+        fn capabilities(&self) -> Capabilities { Capabilities::default().targets(&["x86_64-pc-windows-msvc"]) }
+        fn targets(&self, _state: &State) -> Vec<Option<String>> { vec![Some("wasm32-unknown-unknown".into())] }
+    }
+
+    let state = State::capabilities().unwrap();
+    assert_eq!(capabilities(&Host,     &state).targets, None);
+    assert_eq!(capabilities(&Wasm,     &state).targets, Some(vec!["wasm32-unknown-unknown".into()]));
+    assert_eq!(capabilities(&Explicit, &state).targets, Some(vec!["x86_64-pc-windows-msvc".into()]));
+}
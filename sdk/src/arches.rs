@@ -0,0 +1,35 @@
+use crate::error::*;
+use crate::spec;
+
+use std::collections::BTreeSet;
+
+
+
+#[derive(Debug, Default)]
+pub struct Arches(BTreeSet<String>);
+
+impl Arches {
+    pub fn get() -> Result<Self> {
+        Ok(Self(match opt_var(spec::env::ARCHES)? {
+            None                    => Default::default(),
+            Some(s) if s.is_empty() => Default::default(),
+            Some(s)                 => s.split(',').map(String::from).collect()
+        }))
+    }
+
+    /// `Some(true)` if `arch` was asked for, `None` if the tool should decide (native), `Some(false)` otherwise
+    pub fn contains(&self, arch: &str) -> Option<bool> {
+        if      self.0.contains("*") || self.0.contains(arch)               { Some(true) }
+        else if self.0.is_empty() || self.0.contains(spec::NATIVE_ARCH)     { None }
+        else                                                                { Some(false) }
+    }
+}
+
+#[test] fn test_contains() {
+    let arches = |a: &[&str]| Arches(a.iter().map(|a| a.to_string()).collect());
+    assert_eq!(arches(&[]).contains("x86_64"), None);
+    assert_eq!(arches(&["*"]).contains("x86_64"), Some(true));
+    assert_eq!(arches(&["wasm32"]).contains("x86_64"), Some(false));
+    assert_eq!(arches(&["native", "wasm32"]).contains("wasm32"), Some(true));
+    assert_eq!(arches(&["native", "wasm32"]).contains("x86_64"), None);
+}
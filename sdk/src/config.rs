@@ -0,0 +1,63 @@
+use crate::error::*;
+use crate::spec;
+
+use mmrbi::Command;
+
+
+
+/// A configuration (e.g. `debug`, `release`, or a custom `[config.name]` from `Container.toml`)
+#[derive(Debug)]
+pub struct Config {
+    name:       String,
+    profile:    String,
+    features:   Vec<String>,
+    rustflags:  Vec<String>,
+}
+
+impl Config {
+    pub fn list() -> Result<Vec<Config>> { req_var(spec::env::CONFIGS)?.split(',').filter(|c| !c.is_empty()).map(Config::get).collect() }
+
+    fn get(name: &str) -> Result<Config> {
+        // Fall back on the built-in configs if cargo-container didn't describe this one
+        let default_profile = match name { "debug" => "dev", other => other };
+        Ok(Config {
+            profile:    opt_var(&spec::env::config_profile(name))?.unwrap_or_else(|| default_profile.into()),
+            features:   opt_var(&spec::env::config_features(name))?.map_or(Vec::new(), |f| f.split(',').filter(|f| !f.is_empty()).map(String::from).collect()),
+            rustflags:  opt_var(&spec::env::config_rustflags(name))?.map_or(Vec::new(), |f| f.split(spec::env::RUSTFLAGS_SEPARATOR).filter(|f| !f.is_empty()).map(String::from).collect()),
+            name:       name.into(),
+        })
+    }
+
+    /// The configuration's name, e.g. `"debug"` or `"profiling"`
+    pub fn name(&self) -> &str { &self.name }
+
+    /// The cargo profile to build with, e.g. `"dev"`, `"release"`, or `"release-with-debug"`
+    pub fn profile(&self) -> &str { &self.profile }
+
+    /// The directory cargo puts this profile's outputs in, e.g. `"debug"` for `target/debug`
+    pub fn profile_dir(&self) -> &str {
+        match self.profile() {
+            "dev" | "test"  => "debug",
+            "bench"         => "release",
+            other           => other,
+        }
+    }
+
+    /// Extra cargo features to enable
+    pub fn features(&self) -> &[String] { &self.features }
+
+    /// Extra flags to pass to rustc
+    pub fn rustflags(&self) -> &[String] { &self.rustflags }
+
+    /// Add `--profile`, `--features`, and rustflags to a `cargo build|test|run|...` command
+    pub fn apply_to(&self, cmd: &mut Command) {
+        cmd.arg("--profile").arg(self.profile());
+        self.apply_extras_to(cmd);
+    }
+
+    /// Add `--features` and rustflags to a command wrapping cargo that has its own idea of profiles (`wasm-pack`, `cargo web`, ...)
+    pub fn apply_extras_to(&self, cmd: &mut Command) {
+        if !self.features.is_empty() { cmd.arg("--features").arg(self.features.join(",")); }
+        if !self.rustflags.is_empty() { cmd.env("CARGO_ENCODED_RUSTFLAGS", self.rustflags.join(&spec::env::RUSTFLAGS_SEPARATOR.to_string())); }
+    }
+}
@@ -0,0 +1,48 @@
+//! Emit `cargo-container:json=...` directives for `cargo container` to pick up.
+//!
+//! Directives are appended to the file named by `CARGO_CONTAINER_DIRECTIVES` when set, leaving stdout alone.
+//! Older versions of `cargo container` don't set it, in which case directives are written to stdout instead.
+
+use crate::error::*;
+use crate::spec;
+
+use serde_json::Value;
+
+use std::io::Write;
+use std::path::Path;
+
+
+
+pub use crate::spec::directive::{Diagnostic, Directive, Severity};
+
+impl Diagnostic {
+    pub fn emit(&self) -> Result<()> { emit(Directive::Diagnostic(self.clone())) }
+}
+
+/// Request `command` be run as an administrator (Windows) or root (Linux, OS X, ...)
+pub fn sudo(command: &str) -> Result<()> { emit(Directive::Sudo { command: command.into() }) }
+
+/// Request `apt-get install -y [package]` be run as root on linux.
+pub fn apt_get_install(package: &str) -> Result<()> { emit(Directive::AptGetInstall { package: package.into() }) }
+
+/// Request `generate` be rerun if `path` changes (on top of `cargo container`'s own checks)
+pub fn rerun_if_changed(path: impl AsRef<Path>) -> Result<()> { emit(Directive::RerunIfChanged { path: path.as_ref().into() }) }
+
+/// Request entries be merged into the `.cargo/config.toml` generated alongside `Container.toml`, e.g. `json!({ "target": { "x86_64-pc-windows-gnu": { "linker": "..." } } })`
+pub fn cargo_config(config: Value) -> Result<()> {
+    match config {
+        Value::Object(config) => emit(Directive::CargoConfig { config }),
+        other => Err(Error::InvalidDirective { reason: format!("`cargo-config` expected an object, got {}", other) }),
+    }
+}
+
+fn emit(directive: Directive) -> Result<()> {
+    match opt_var_path(spec::env::DIRECTIVES) {
+        None => println!("{}", directive.to_line()),
+        Some(path) => {
+            let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&path).map_err(|err| Error::io(&path, err))?;
+            writeln!(file, "{}", directive.to_line()).map_err(|err| Error::io(&path, err))?;
+        },
+    }
+    Ok(())
+}
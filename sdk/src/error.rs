@@ -0,0 +1,78 @@
+use std::fmt::{self, Display, Formatter};
+use std::path::{Path, PathBuf};
+
+
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Something about how the tool was invoked didn't match the protocol.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A required environment variable wasn't set (is the tool being run outside of `cargo container`?)
+    MissingVar { name: String },
+
+    /// An environment variable was set, but wasn't valid
+    InvalidVar { name: String, reason: String },
+
+    /// Reading or writing a file failed
+    Io { path: PathBuf, error: std::io::Error },
+
+    /// A command that only operates on a single package or config was given several (or none)
+    ExpectedSingle { command: String, what: &'static str, count: usize },
+
+    /// A directive couldn't be written as requested
+    InvalidDirective { reason: String },
+
+    /// A program the tool runs (e.g. `cargo clippy`) couldn't be launched or waited on
+    Command { command: String, error: std::io::Error },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Error::MissingVar { name }                  => write!(f, "{} not set: did you not run this via `cargo container`?", name),
+            Error::InvalidVar { name, reason }          => write!(f, "{}: {}", name, reason),
+            Error::Io { path, error }                   => write!(f, "`{}`: {}", path.display(), error),
+            Error::ExpectedSingle { command, what, count } => write!(f, "`{}` expected a single {}, got {}", command, what, count),
+            Error::InvalidDirective { reason }          => write!(f, "invalid directive: {}", reason),
+            Error::Command { command, error }           => write!(f, "{} failed: {}", command, error),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { error, .. } => Some(error),
+            Error::Command { error, .. } => Some(error),
+            _                       => None,
+        }
+    }
+}
+
+impl Error {
+    pub(crate) fn io(path: impl AsRef<Path>, error: std::io::Error) -> Self { Error::Io { path: path.as_ref().into(), error } }
+}
+
+
+
+pub(crate) fn opt_var(name: &str) -> Result<Option<String>> {
+    match std::env::var(name) {
+        Ok(value)                               => Ok(Some(value)),
+        Err(std::env::VarError::NotPresent)     => Ok(None),
+        Err(std::env::VarError::NotUnicode(_))  => Err(Error::InvalidVar { name: name.into(), reason: "not valid unicode".into() }),
+    }
+}
+
+pub(crate) fn req_var(name: &str) -> Result<String> {
+    opt_var(name)?.ok_or_else(|| Error::MissingVar { name: name.into() })
+}
+
+pub(crate) fn opt_var_path(name: &str) -> Option<PathBuf> {
+    std::env::var_os(name).map(PathBuf::from)
+}
+
+pub(crate) fn req_var_path(name: &str) -> Result<PathBuf> {
+    opt_var_path(name).ok_or_else(|| Error::MissingVar { name: name.into() })
+}
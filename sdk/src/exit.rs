@@ -0,0 +1,42 @@
+//! Exit with one of the [spec::exit](crate::spec::exit) codes `cargo container` understands.
+
+pub use crate::spec::exit::*;
+
+use std::fmt::Display;
+
+pub fn errors()                     -> ! { std::process::exit(ERRORS) }
+pub fn warnings()                   -> ! { std::process::exit(WARNINGS) }
+pub fn command_not_implemented()    -> ! { std::process::exit(COMMAND_NOT_IMPLEMENTED) }
+pub fn platform_not_implemented()   -> ! { std::process::exit(PLATFORM_NOT_IMPLEMENTED) }
+
+/// Exit with the exit code of a program launched by `run`
+pub fn status(status: std::process::ExitStatus) -> ! { std::process::exit(status.code().unwrap_or(ERRORS)) }
+
+/// Report an error, then exit with [ERRORS].
+///
+/// Prefer this over `mmrbi::fatal!` for failed commands: `fatal!` exits with `1`, which `cargo container` reports as a crash.
+#[macro_export] macro_rules! fail {
+    ( $($tt:tt)* ) => {{
+        $crate::mmrbi::error!($($tt)*);
+        $crate::exit::errors()
+    }};
+}
+
+/// Like `mmrbi::ResultExt`, but exits with [ERRORS] (see [fail!](crate::fail))
+pub trait OrFail {
+    type Output;
+
+    /// Shorthand for `result.unwrap_or_else(|err| fail!("{}", err))`
+    fn or_fail(self) -> Self::Output;
+}
+
+impl<T, E: Display> OrFail for Result<T, E> {
+    type Output = T;
+
+    fn or_fail(self) -> Self::Output {
+        match self {
+            Ok(r) => r,
+            Err(err) => crate::fail!("{}", err),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Run `cargo ... --message-format=json`, forwarding rustc's diagnostics to `cargo container` as directives.
+
+use crate::State;
+use crate::directive::{Diagnostic, Severity};
+use crate::error::*;
+use crate::spec::exit;
+
+use mmrbi::Command;
+
+use serde_json::Value;
+
+use std::collections::BTreeSet;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::process::{ExitStatus, Stdio};
+
+
+
+/// `cargo clippy` `state`'s generated packages for each of `targets`, returning the [exit] code the tool should exit with.
+pub(crate) fn clippy(state: &State, targets: &[Option<String>]) -> Result<i32> {
+    let mut lints = Lints::default();
+    let mut failed = false;
+    for config in state.configs.iter() {
+        for target in targets.iter() {
+            let mut cmd = Command::new("cargo");
+            cmd.args(["clippy", "--message-format=json"]);
+            if let Some(target) = target.as_ref() { cmd.arg("--target").arg(target); }
+            config.apply_to(&mut cmd);
+            for package in state.packages.iter() { cmd.arg("-p").arg(package.generated_name()); }
+            if !state.args.is_empty() { cmd.arg("--").args(&state.args); }
+            failed |= !lints.run(&cmd)?.success();
+        }
+    }
+    Ok(if failed || lints.errors > 0 { exit::ERRORS } else if lints.warnings > 0 { exit::WARNINGS } else { exit::SUCCESS })
+}
+
+#[derive(Default)]
+struct Lints {
+    seen:           BTreeSet<String>,
+    errors:     usize,
+    warnings:   usize,
+}
+
+impl Lints {
+    /// Run `cmd` (which should include `--message-format=json`), emitting each diagnostic not already seen.  stderr is inherited.
+    fn run(&mut self, cmd: &Command) -> Result<ExitStatus> {
+        let failed = |error| Error::Command { command: cmd.to_string(), error };
+        let mut child = cmd.to_command().stdout(Stdio::piped()).stderr(Stdio::inherit()).spawn().map_err(failed)?;
+        for line in BufReader::new(child.stdout.take().unwrap()).lines() {
+            let message = match serde_json::from_str::<Value>(&line.map_err(failed)?) {
+                Ok(message) => message,
+                Err(_)      => continue, // not JSON - e.g. a build script writing to stdout
+            };
+            if message["reason"] != "compiler-message" { continue }
+            self.diagnostic(&message["message"])?;
+        }
+        child.wait().map_err(failed)
+    }
+
+    fn diagnostic(&mut self, message: &Value) -> Result<()> {
+        let severity = match message["level"].as_str().unwrap_or("") {
+            "error" | "error: internal compiler error"  => Severity::Error,
+            "warning"                                   => Severity::Warning,
+            _                                           => return Ok(()), // e.g. "failure-note: For more information about this error, try `rustc --explain E0308`."
+        };
+
+        // The same lint in a crate shared by several targets or configs only needs reporting once
+        let rendered = message["rendered"].as_str().unwrap_or("");
+        if !self.seen.insert(rendered.into()) { return Ok(()) }
+        match severity {
+            Severity::Error     => self.errors += 1,
+            Severity::Warning   => self.warnings += 1,
+            Severity::Info      => {},
+        }
+
+        let span = message["spans"].as_array().and_then(|spans| spans.iter().find(|span| span["is_primary"] == true));
+        let file = span.and_then(|span| span["file_name"].as_str()).map(Path::new);
+
+        // `rendered` starts with "warning: {message}" and " --> {file}:{line}:{column}" - keep the source snippet that follows
+        let headline = message["message"].as_str().unwrap_or("");
+        let mut snippet = rendered.trim_end().lines().skip(1).peekable();
+        if file.is_some() && snippet.peek().is_some_and(|line| line.trim_start().starts_with("-->")) { snippet.next(); }
+        let snippet = snippet.collect::<Vec<_>>();
+        let text = if snippet.is_empty() { headline.to_string() } else { format!("{}\n{}", headline, snippet.join("\n")) };
+
+        let mut diagnostic = Diagnostic::new(severity, &text);
+        if let Some(code) = message["code"]["code"].as_str() { diagnostic = diagnostic.code(code); }
+        if let (Some(span), Some(file)) = (span, file) {
+            diagnostic = diagnostic.at(file, span["line_start"].as_u64().unwrap_or(0) as usize, span["column_start"].as_u64().unwrap_or(0) as usize);
+        }
+        diagnostic.emit()
+    }
+}
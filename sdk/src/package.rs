@@ -0,0 +1,115 @@
+use crate::error::*;
+use crate::spec;
+
+use serde_json::Value;
+
+use std::path::{Path, PathBuf};
+
+
+
+#[derive(Debug)]
+pub struct Package {
+    // e.g. "alpha"
+    name:           String,
+    // e.g. "alpha-windows"
+    name_suffix:    String,
+    // e.g. ".container/crates/platform-windows/alpha"
+    generated_path: PathBuf,
+    // e.g. "../../../../apps/alpha"
+    original_path:  PathBuf,
+    version:        String,
+    description:    String,
+    // `cargo metadata`'s description of the package, from `CARGO_CONTAINER_MANIFEST` (or `null` for older versions of `cargo container`)
+    info:           Value,
+}
+
+impl Package {
+    pub fn list(suffix: &str) -> Result<Vec<Package>> {
+        let manifest = match opt_var_path(spec::env::MANIFEST) {
+            None => Value::Null,
+            Some(path) => {
+                let text = std::fs::read_to_string(&path).map_err(|err| Error::io(&path, err))?;
+                serde_json::from_str(&text).map_err(|err| Error::InvalidVar { name: spec::env::MANIFEST.into(), reason: format!("unable to parse `{}`: {}", path.display(), err) })?
+            },
+        };
+        if let Some(version) = manifest.get("version").and_then(Value::as_u64) {
+            if version > u64::from(spec::manifest::VERSION) {
+                return Err(Error::InvalidVar { name: spec::env::MANIFEST.into(), reason: format!("manifest version {} is newer than the newest supported version ({})", version, spec::manifest::VERSION) });
+            }
+        }
+        Self::from_manifest(&manifest, &req_var(spec::env::PACKAGES)?, &req_var_path(spec::env::CRATES_DIR)?, suffix)
+    }
+
+    /// Describe the `,`-separated `names` using `manifest`, falling back on the older `CARGO_CONTAINER_PACKAGE_*` env vars for packages it doesn't describe.
+    fn from_manifest(manifest: &Value, names: &str, crates_dir: &Path, suffix: &str) -> Result<Vec<Package>> {
+        let packages = manifest.get("packages").and_then(Value::as_array).map_or(&[][..], |p| &p[..]);
+
+        names.split(',').filter(|p| !p.is_empty()).map(|p| {
+            let info = packages.iter().find(|info| info["name"] == p).cloned().unwrap_or(Value::Null);
+            let original_path = match info["directory"].as_str() {
+                Some(dir)   => Path::new("../../../..").join(dir),
+                None        => req_var_path(&spec::env::package_path(p))?,
+            };
+            // Described packages may simply not have a version or description - only undescribed packages fall back on env vars
+            let version = match info["version"].as_str() {
+                Some(version)                   => version.into(),
+                None if info.is_object()        => String::new(),
+                None                            => req_var(&spec::env::package_version(p))?,
+            };
+            let description = match info["description"].as_str() {
+                Some(desc)                      => desc.into(),
+                None if info.is_object()        => String::new(),
+                None                            => req_var(&spec::env::package_description(p))?,
+            };
+            Ok(Package {
+                name_suffix:    format!("{}-{}", p, suffix),
+                name:           p.into(),
+                generated_path: crates_dir.join(p),
+                original_path,
+                version,
+                description,
+                info,
+            })
+        }).collect()
+    }
+
+    pub fn original_name(&self)     -> &str     { &self.name }
+    pub fn generated_name(&self)    -> &str     { &self.name_suffix }
+    pub fn generated_path(&self)    -> &Path    { &self.generated_path }
+
+    /// The original package's directory, relative to [Package::generated_path]
+    pub fn original_path(&self)     -> &Path    { &self.original_path }
+    pub fn version(&self)           -> &str     { &self.version }
+    pub fn description(&self)       -> &str     { &self.description }
+
+    pub fn authors(&self)           -> Vec<&str>        { self.info["authors"].as_array().map_or(Vec::new(), |a| a.iter().filter_map(Value::as_str).collect()) }
+    pub fn license(&self)           -> Option<&str>     { self.info["license"].as_str() }
+    pub fn repository(&self)        -> Option<&str>     { self.info["repository"].as_str() }
+    pub fn edition(&self)           -> Option<&str>     { self.info["edition"].as_str() }
+    /// Names of the features the package defines
+    pub fn features(&self)          -> Vec<&str>        { self.info["features"].as_object().map_or(Vec::new(), |f| f.keys().map(|k| k.as_str()).collect()) }
+    /// `[package.metadata]` (or `null`)
+    pub fn metadata(&self)          -> &Value           { &self.info["metadata"] }
+    /// `[package.metadata.container] name`, or the package name
+    pub fn display_name(&self)      -> &str             { self.info["container"]["name"].as_str().unwrap_or(&self.name) }
+    /// `[package.metadata.container] icon`, relative to `Container.toml`
+    pub fn icon(&self)              -> Option<&Path>    { self.info["container"]["icon"].as_str().map(Path::new) }
+    /// `[package.metadata.container] id`, a reverse-DNS application id like `com.example.alpha`
+    pub fn app_id(&self)            -> Option<&str>     { self.info["container"]["id"].as_str() }
+    /// `[package.metadata.container] category`, e.g. `"game"` or `"utility"` (one of [spec::manifest::APP_CATEGORIES])
+    pub fn category(&self)          -> Option<&str>     { self.info["container"]["category"].as_str() }
+    /// `[package.metadata.container] terminal`: `Some(true)` for console apps, `Some(false)` for GUI apps
+    pub fn terminal(&self)          -> Option<bool>     { self.info["container"]["terminal"].as_bool() }
+    /// Everything `cargo metadata` had to say about the package (or `null` for older versions of `cargo container`)
+    pub fn info(&self)              -> &Value           { &self.info }
+}
+
+#[test] fn test_from_manifest_without_description() {
+    let manifest = serde_json::json!({ "version": 1, "packages": [{ "name": "alpha", "version": "0.0.0", "directory": "apps/alpha", "container": {} }] });
+    let packages = Package::from_manifest(&manifest, "alpha", Path::new(".container/crates/platform-console"), "console").unwrap();
+    assert_eq!(packages.len(), 1);
+    assert_eq!(packages[0].generated_name(), "alpha-console");
+    assert_eq!(packages[0].original_path(), Path::new("../../../../apps/alpha"));
+    assert_eq!(packages[0].version(), "0.0.0");
+    assert_eq!(packages[0].description(), "");
+}
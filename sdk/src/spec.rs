@@ -0,0 +1,355 @@
+//! The protocol between `cargo container` and the tools it invokes.
+//!
+//! `cargo container` itself depends on this module, so anything here is what the core actually sends and expects.
+
+use serde_json::{json, Value};
+
+use std::convert::TryFrom;
+
+
+
+/// The newest protocol version (see [env::PROTOCOL_VERSION] and [Capabilities::protocol])
+pub const PROTOCOL_VERSION : u32 = 1;
+
+/// Commands `cargo container` invokes tools with (via [env::COMMAND])
+pub const COMMANDS : &[&str] = &["bench", "build", "check", "clean", "clippy", "deploy", "doc", "fetch", "fuzz", "generate", "package", "run", "setup", "test", "update"];
+
+/// The [env::COMMAND] asking a tool to print its [Capabilities] as JSON to stdout, and exit successfully.
+///
+/// Only [env::COMMAND] and [env::PROTOCOL_VERSION] are set for this command.
+/// Tools predating it will typically exit with [exit::COMMAND_NOT_IMPLEMENTED], which means "unknown - assume anything goes".
+pub const CAPABILITIES : &str = "capabilities";
+
+/// Listed in [env::ARCHES] alongside explicit arches when some builds also want whatever the tool considers native, e.g. `"native,wasm32"`
+pub const NATIVE_ARCH : &str = "native";
+
+/// What a tool supports, as reported by the [CAPABILITIES] command.
+///
+/// ```json
+/// { "protocol": 1, "commands": ["build", "generate", "run"], "hosts": ["windows", "linux"], "arches": ["x86_64"], "targets": ["x86_64-pc-windows-msvc"] }
+/// ```
+///
+/// Any list left out (`None`) means "unknown - assume anything goes."
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Capabilities {
+    /// The protocol version the tool speaks
+    pub protocol:   u32,
+    /// The [COMMANDS] the tool implements
+    pub commands:   Option<Vec<String>>,
+    /// Host OSes the tool runs on, as named by [std::env::consts::OS] (`"windows"`, `"linux"`, `"macos"`, ...)
+    pub hosts:      Option<Vec<String>>,
+    /// Architectures the tool can build for (`"x86_64"`, `"wasm32"`, ...)
+    pub arches:     Option<Vec<String>>,
+    /// Configs the tool can build (`"debug"`, `"release"`, ...)
+    pub configs:    Option<Vec<String>>,
+    /// Target triples the tool's generated crates build for, used to `cargo check` them for tools that don't implement `check` (`None` means the host's).
+    /// The SDK fills this in from [Tool::targets](crate::Tool::targets).
+    pub targets:    Option<Vec<String>>,
+}
+
+impl Default for Capabilities {
+    fn default() -> Self { Self { protocol: PROTOCOL_VERSION, commands: None, hosts: None, arches: None, configs: None, targets: None } }
+}
+
+impl Capabilities {
+    pub fn commands (self, commands: &[&str]) -> Self { Self { commands: Some(commands.iter().map(|c| c.to_string()).collect()), ..self } }
+    pub fn hosts    (self, hosts:    &[&str]) -> Self { Self { hosts:    Some(hosts   .iter().map(|h| h.to_string()).collect()), ..self } }
+    pub fn arches   (self, arches:   &[&str]) -> Self { Self { arches:   Some(arches  .iter().map(|a| a.to_string()).collect()), ..self } }
+    pub fn configs  (self, configs:  &[&str]) -> Self { Self { configs:  Some(configs .iter().map(|c| c.to_string()).collect()), ..self } }
+    pub fn targets  (self, targets:  &[&str]) -> Self { Self { targets:  Some(targets .iter().map(|t| t.to_string()).collect()), ..self } }
+
+    pub fn supports_command (&self, command: &str) -> bool { supports(&self.commands, command) }
+    pub fn supports_host    (&self, host:    &str) -> bool { supports(&self.hosts,    host   ) }
+    pub fn supports_arch    (&self, arch:    &str) -> bool { supports(&self.arches,   arch   ) }
+    pub fn supports_config  (&self, config:  &str) -> bool { supports(&self.configs,  config ) }
+
+    pub fn to_json(&self) -> Value {
+        let mut o = json!({ "protocol": self.protocol });
+        if let Some(v) = self.commands.as_ref()  { o["commands"] = v.clone().into(); }
+        if let Some(v) = self.hosts.as_ref()     { o["hosts"]    = v.clone().into(); }
+        if let Some(v) = self.arches.as_ref()    { o["arches"]   = v.clone().into(); }
+        if let Some(v) = self.configs.as_ref()   { o["configs"]  = v.clone().into(); }
+        if let Some(v) = self.targets.as_ref()   { o["targets"]  = v.clone().into(); }
+        o
+    }
+
+    pub fn from_json(value: &Value) -> Result<Self, String> {
+        let protocol = value.get("protocol").ok_or("expected a `protocol` version")?;
+        let protocol = protocol.as_u64().and_then(|p| u32::try_from(p).ok()).ok_or_else(|| format!("expected `protocol` to be a version number, got {}", protocol))?;
+        let list = |key: &str| -> Result<Option<Vec<String>>, String> {
+            match value.get(key) {
+                None | Some(Value::Null) => Ok(None),
+                Some(Value::Array(a)) => a.iter().map(|v| v.as_str().map(String::from).ok_or_else(|| format!("expected `{}` to be an array of strings, got {}", key, v))).collect::<Result<_, _>>().map(Some),
+                Some(other) => Err(format!("expected `{}` to be an array of strings, got {}", key, other)),
+            }
+        };
+        Ok(Self { protocol, commands: list("commands")?, hosts: list("hosts")?, arches: list("arches")?, configs: list("configs")?, targets: list("targets")? })
+    }
+}
+
+fn supports(list: &Option<Vec<String>>, item: &str) -> bool {
+    list.as_ref().map_or(true, |list| list.iter().any(|i| i == item))
+}
+
+/// Environment variables `cargo container` invokes tools with.
+pub mod env {
+    /// The command being run, e.g. `"build"` (one of [COMMANDS](super::COMMANDS), or [CAPABILITIES](super::CAPABILITIES))
+    pub const COMMAND       : &str = "CARGO_CONTAINER_COMMAND";
+
+    /// The [PROTOCOL_VERSION](super::PROTOCOL_VERSION) `cargo container` speaks, e.g. `"1"` (unset by versions predating the handshake)
+    pub const PROTOCOL_VERSION : &str = "CARGO_CONTAINER_PROTOCOL_VERSION";
+
+    /// The directory to generate crates into, e.g. `".container/crates/platform-console"`
+    pub const CRATES_DIR    : &str = "CARGO_CONTAINER_CRATES_DIR";
+
+    /// `,`-separated architectures to build for (`"*"` for all, empty or [NATIVE_ARCH](super::NATIVE_ARCH) for whatever the tool considers native)
+    pub const ARCHES        : &str = "CARGO_CONTAINER_ARCHES";
+
+    /// `,`-separated names of the packages to operate on
+    pub const PACKAGES      : &str = "CARGO_CONTAINER_PACKAGES";
+
+    /// `,`-separated names of the configs to operate on, e.g. `"debug,release"`
+    pub const CONFIGS       : &str = "CARGO_CONTAINER_CONFIGS";
+
+    /// A JSON array of the arguments passed after `--`
+    pub const ARGS          : &str = "CARGO_CONTAINER_ARGS";
+
+    /// A file to append `cargo-container:...` directives to, instead of writing them to stdout
+    pub const DIRECTIVES    : &str = "CARGO_CONTAINER_DIRECTIVES";
+
+    /// A JSON file describing the packages (see [manifest](super::manifest))
+    pub const MANIFEST      : &str = "CARGO_CONTAINER_MANIFEST";
+
+    /// Separates the flags of [config_rustflags] (the same as `CARGO_ENCODED_RUSTFLAGS`)
+    pub const RUSTFLAGS_SEPARATOR : char = '\x1f';
+
+    /// `config` as it appears in [config_profile] etc: uppercased, with anything but ASCII letters, digits, and `_` replaced by `_` (e.g. `release-lto` → `RELEASE_LTO`)
+    pub fn config_name(config: &str) -> String {
+        config.chars().map(|ch| if ch.is_ascii_alphanumeric() { ch.to_ascii_uppercase() } else { '_' }).collect()
+    }
+
+    /// The cargo profile `config` builds with
+    pub fn config_profile(config: &str) -> String { format!("CARGO_CONTAINER_CONFIG_{}_PROFILE", config_name(config)) }
+
+    /// `,`-separated extra features `config` enables
+    pub fn config_features(config: &str) -> String { format!("CARGO_CONTAINER_CONFIG_{}_FEATURES", config_name(config)) }
+
+    /// [RUSTFLAGS_SEPARATOR]-separated extra flags `config` passes to rustc
+    pub fn config_rustflags(config: &str) -> String { format!("CARGO_CONTAINER_CONFIG_{}_RUSTFLAGS", config_name(config)) }
+
+    /// The original package's directory, relative to the generated crate.  Only set for `generate` - prefer [MANIFEST].
+    pub fn package_path(package: &str) -> String { format!("CARGO_CONTAINER_PACKAGE_{}_PATH", package) }
+
+    /// The package's version.  Only set for `generate` - prefer [MANIFEST].
+    pub fn package_version(package: &str) -> String { format!("CARGO_CONTAINER_PACKAGE_{}_VERSION", package) }
+
+    /// The package's description.  Only set for `generate` - prefer [MANIFEST].
+    pub fn package_description(package: &str) -> String { format!("CARGO_CONTAINER_PACKAGE_{}_DESCRIPTION", package) }
+}
+
+/// Tool exit codes.
+pub mod exit {
+    pub const SUCCESS                   : i32 = 0x00;
+    pub const ERRORS                    : i32 = 0xEE; // EE = EE = Errors
+    pub const WARNINGS                  : i32 = 0x33; // 33 = WW = Warnings
+    pub const COMMAND_NOT_IMPLEMENTED   : i32 = 0xC1; // C1 = CnI = Command Not Implemented
+    pub const PLATFORM_NOT_IMPLEMENTED  : i32 = 0x91; // 91 = PnI = Platform Not Implemented
+}
+
+/// `cargo-container:...` directives tools write back.
+///
+/// ```text
+/// cargo-container:json={"version":1,"type":"diagnostic","severity":"warning","message":"...","file":"src/lib.rs","line":3,"column":5}
+/// ```
+pub mod directive {
+    use serde_json::{json, Map, Value};
+
+    use std::convert::TryFrom;
+    use std::path::PathBuf;
+
+    /// The prefix all directives start with.
+    pub const PREFIX : &str = "cargo-container:";
+
+    /// The newest `cargo-container:json=...` version.
+    pub const JSON_VERSION : u32 = 1;
+
+    /// A `cargo-container:json=...` directive
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum Directive {
+        /// Run `command` as an administrator (Windows) or root (Linux, OS X, ...)
+        Sudo { command: String },
+        /// `apt-get install -y [package]` as root, deduplicated with other requests
+        AptGetInstall { package: String },
+        /// Rerun `generate` if this path changes
+        RerunIfChanged { path: PathBuf },
+        /// Merge these entries into the generated `.cargo/config.toml`
+        CargoConfig { config: Map<String, Value> },
+        /// Display an error, warning, or info message
+        Diagnostic(Diagnostic),
+    }
+
+    /// A diagnostic message, optionally pointing at a location in a file.
+    ///
+    /// Unlike `cargo-container:error=...`, `message` may span multiple lines.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct Diagnostic {
+        pub severity:   Severity,
+        pub message:    String,
+        pub code:       Option<String>,
+        pub file:       Option<PathBuf>,
+        pub line:       usize,
+        pub column:     usize,
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum Severity {
+        Error,
+        Warning,
+        Info,
+    }
+
+    impl Diagnostic {
+        pub fn new(severity: Severity, message: impl Into<String>) -> Self { Self { severity, message: message.into(), code: None, file: None, line: 0, column: 0 } }
+        pub fn error  (message: impl Into<String>) -> Self { Self::new(Severity::Error,   message) }
+        pub fn warning(message: impl Into<String>) -> Self { Self::new(Severity::Warning, message) }
+        pub fn info   (message: impl Into<String>) -> Self { Self::new(Severity::Info,    message) }
+
+        pub fn code(self, code: impl Into<String>) -> Self { Self { code: Some(code.into()), ..self } }
+        pub fn at(self, file: impl Into<PathBuf>, line: usize, column: usize) -> Self { Self { file: Some(file.into()), line, column, ..self } }
+    }
+
+    impl Severity {
+        pub fn as_str(self) -> &'static str {
+            match self {
+                Severity::Error     => "error",
+                Severity::Warning   => "warning",
+                Severity::Info      => "info",
+            }
+        }
+    }
+
+    impl Directive {
+        /// The whole line, e.g. `cargo-container:json={"version":1,"type":"sudo","command":"..."}`
+        pub fn to_line(&self) -> String { format!("{}json={}", PREFIX, self.to_json()) }
+
+        pub fn to_json(&self) -> Value {
+            let mut o = match self {
+                Directive::Sudo { command }             => json!({ "type": "sudo",              "command":  command }),
+                Directive::AptGetInstall { package }    => json!({ "type": "apt-get-install",   "package":  package }),
+                Directive::RerunIfChanged { path }      => json!({ "type": "rerun-if-changed",  "path":     path }),
+                Directive::CargoConfig { config }       => json!({ "type": "cargo-config",      "config":   config }),
+                Directive::Diagnostic(d) => {
+                    let mut o = json!({ "type": "diagnostic", "severity": d.severity.as_str(), "message": d.message });
+                    if let Some(code) = d.code.as_ref() { o["code"] = code.as_str().into(); }
+                    if let Some(file) = d.file.as_ref() {
+                        o["file"]   = file.to_string_lossy().into();
+                        o["line"]   = d.line.into();
+                        o["column"] = d.column.into();
+                    }
+                    o
+                },
+            };
+            o["version"] = JSON_VERSION.into();
+            o
+        }
+
+        pub fn from_json(value: &Value) -> Result<Self, String> {
+            let version = value.get("version").and_then(|v| v.as_u64()).ok_or("expected a `version` number")?;
+            if version == 0 { return Err("invalid version 0".into()) }
+            if version > u64::from(JSON_VERSION) { return Err(format!("unsupported version {} (newest supported version is {})", version, JSON_VERSION)) }
+
+            let string = |key: &str| value.get(key).and_then(|v| v.as_str()).map(String::from).ok_or_else(|| format!("expected `{}` to be a string", key));
+            let usize = |key: &str| match value.get(key) {
+                None | Some(Value::Null) => Ok(0),
+                Some(v) => v.as_u64().and_then(|n| usize::try_from(n).ok()).ok_or_else(|| format!("expected `{}` to be a number, got {}", key, v)),
+            };
+            match value.get("type").and_then(|t| t.as_str()) {
+                Some("sudo")                => Ok(Directive::Sudo { command: string("command")? }),
+                Some("apt-get-install")     => Ok(Directive::AptGetInstall { package: string("package")? }),
+                Some("rerun-if-changed")    => Ok(Directive::RerunIfChanged { path: string("path")?.into() }),
+                Some("cargo-config")        => match value.get("config") {
+                    Some(Value::Object(config)) => Ok(Directive::CargoConfig { config: config.clone() }),
+                    _ => Err("expected `config` to be an object".into()),
+                },
+                Some("diagnostic")          => Ok(Directive::Diagnostic(Diagnostic {
+                    severity: match string("severity")?.as_str() {
+                        "error"     => Severity::Error,
+                        "warning"   => Severity::Warning,
+                        "info"      => Severity::Info,
+                        other       => return Err(format!("unknown severity `{}`", other)),
+                    },
+                    message:    string("message")?,
+                    code:       value.get("code").and_then(|c| c.as_str()).map(String::from),
+                    file:       value.get("file").and_then(|f| f.as_str()).map(PathBuf::from),
+                    line:       usize("line")?,
+                    column:     usize("column")?,
+                })),
+                Some(other)                 => Err(format!("unknown type `{}`", other)),
+                None                        => Err("expected a `type`".into()),
+            }
+        }
+    }
+}
+
+/// The JSON file [env::MANIFEST] points at.
+pub mod manifest {
+    /// The newest manifest version.
+    pub const VERSION : u32 = 1;
+
+    /// Keys understood in `[package.metadata.container]`
+    ///
+    /// ```toml
+    /// [package.metadata.container]
+    /// name        = "Alpha Centauri"      # display name (default: the package name)
+    /// icon        = "icon.png"            # relative to Cargo.toml
+    /// id          = "com.example.alpha"   # reverse-DNS application id
+    /// category    = "game"                # one of APP_CATEGORIES
+    /// terminal    = false                 # does the app need a terminal/console, or is it a GUI app?
+    /// ```
+    pub const APP_KEYS : &[&str] = &["name", "icon", "id", "category", "terminal"];
+
+    /// Valid `[package.metadata.container] category`s (based on the freedesktop.org main categories)
+    pub const APP_CATEGORIES : &[&str] = &["audio", "video", "development", "education", "game", "graphics", "network", "office", "science", "settings", "system", "utility"];
+}
+
+
+
+#[test] fn test_capabilities_json() {
+    let caps = Capabilities::default().commands(&["build", "run"]).hosts(&["windows"]).targets(&["wasm32-unknown-unknown"]);
+    assert_eq!(Capabilities::from_json(&caps.to_json()), Ok(caps.clone()));
+    assert!( caps.supports_command("build"));
+    assert!(!caps.supports_command("test"));
+    assert!( caps.supports_arch("wasm32")); // unspecified
+    assert!(!caps.supports_host("linux"));
+
+    assert_eq!(Capabilities::from_json(&json!({ "protocol": 1 })), Ok(Capabilities { protocol: 1, ..Default::default() }));
+    assert!(Capabilities::from_json(&json!({})).is_err());
+    assert!(Capabilities::from_json(&json!({ "protocol": 1, "commands": "build" })).is_err());
+    assert!(Capabilities::from_json(&json!({ "protocol": 1, "commands": [1] })).is_err());
+}
+
+#[test] fn test_directive_json() {
+    use directive::*;
+    let mut config = serde_json::Map::new();
+    config.insert("target".into(), json!({ "x86_64-pc-windows-gnu": { "runner": "wine" } }));
+    for d in [
+        Directive::Sudo { command: "echo hi".into() },
+        Directive::AptGetInstall { package: "libssl-dev".into() },
+        Directive::RerunIfChanged { path: "assets/icon.png".into() },
+        Directive::CargoConfig { config },
+        Directive::Diagnostic(Diagnostic::warning("multi\nline")),
+        Directive::Diagnostic(Diagnostic::error("bad").code("E0308").at("src/lib.rs", 3, 5)),
+    ] {
+        assert_eq!(Directive::from_json(&d.to_json()), Ok(d.clone()));
+    }
+
+    assert!(Directive::from_json(&json!({ "version": 2, "type": "sudo", "command": "echo hi" })).unwrap_err().contains("version 2"));
+    assert!(Directive::from_json(&json!({ "version": 1, "type": "telemetry" })).is_err());
+    assert!(Directive::from_json(&json!({ "version": 1, "type": "diagnostic", "severity": "fatal", "message": "" })).is_err());
+}
+
+#[test] fn test_config_env_names() {
+    assert_eq!(env::config_profile("debug"),            "CARGO_CONTAINER_CONFIG_DEBUG_PROFILE");
+    assert_eq!(env::config_features("release-lto"),     "CARGO_CONTAINER_CONFIG_RELEASE_LTO_FEATURES");
+    assert_eq!(env::config_rustflags("asan.nightly"),   "CARGO_CONTAINER_CONFIG_ASAN_NIGHTLY_RUSTFLAGS");
+}
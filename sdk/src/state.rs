@@ -0,0 +1,57 @@
+use crate::{Arches, Config, Package};
+use crate::error::*;
+use crate::spec;
+
+
+
+pub struct State {
+    pub command:    String,
+    /// The [spec::PROTOCOL_VERSION] `cargo container` speaks (`None` for versions predating the handshake)
+    pub protocol:   Option<u32>,
+    pub packages:   Vec<Package>,
+    pub configs:    Vec<Config>,
+    pub arches:     Arches,
+    /// Arguments passed after `--` (e.g. `cargo container test -- --nocapture`), to forward to whatever the tool runs
+    pub args:       Vec<String>,
+}
+
+impl State {
+    /// Read everything `cargo container` passed to the tool.  `suffix` is appended to generated crate names, e.g. `"windows"` for `alpha-windows`.
+    pub fn from_env(suffix: &str) -> Result<Self> {
+        let command     = req_var(spec::env::COMMAND)?;
+        let protocol    = match opt_var(spec::env::PROTOCOL_VERSION)? {
+            None            => None,
+            Some(version)   => Some(version.parse().map_err(|err| Error::InvalidVar { name: spec::env::PROTOCOL_VERSION.into(), reason: format!("expected a version number: {}", err) })?),
+        };
+        let configs     = Config::list()?;
+        let packages    = Package::list(suffix)?;
+        let arches      = Arches::get()?;
+        let args        = match opt_var(spec::env::ARGS)? {
+            None        => Vec::new(),
+            Some(args)  => serde_json::from_str(&args).map_err(|err| Error::InvalidVar { name: spec::env::ARGS.into(), reason: format!("expected a JSON array of strings: {}", err) })?,
+        };
+        Ok(Self { command, protocol, packages, configs, arches, args })
+    }
+
+    /// The state [spec::CAPABILITIES] is answered with: no packages or configs, and the default arches
+    pub(crate) fn capabilities() -> Result<Self> {
+        let protocol = opt_var(spec::env::PROTOCOL_VERSION)?.and_then(|version| version.parse().ok());
+        Ok(Self { command: spec::CAPABILITIES.into(), protocol, packages: Vec::new(), configs: Vec::new(), arches: Arches::default(), args: Vec::new() })
+    }
+
+    /// The only package, for commands like `run` that `cargo container` only invokes on a single package
+    pub fn package(&self) -> Result<&Package> {
+        match &self.packages[..] {
+            [package] => Ok(package),
+            other => Err(Error::ExpectedSingle { command: self.command.clone(), what: "package", count: other.len() }),
+        }
+    }
+
+    /// The only config, for commands like `run` that `cargo container` only invokes with a single config
+    pub fn config(&self) -> Result<&Config> {
+        match &self.configs[..] {
+            [config] => Ok(config),
+            other => Err(Error::ExpectedSingle { command: self.command.clone(), what: "config", count: other.len() }),
+        }
+    }
+}
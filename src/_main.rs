@@ -1,10 +1,19 @@
 #![allow(dead_code)] // XXX
+#![allow(clippy::unnecessary_map_or)] // `Option::is_none_or` requires rust 1.82
+
+use cargo_container_sdk::spec;
 
 mod args;           use args::Args;
+mod capabilities;
 mod container_toml; use container_toml::ContainerToml;
+mod directive;      use directive::{Directive, Report};
+mod exit;
+mod fingerprint;    use fingerprint::Fingerprint;
 mod generate;
+mod invoke;
+mod manifest;
 mod run;
-mod toml_util;
+mod validate;
 mod env_utils;      use env_utils::*;
 
 fn main() { run::run() }
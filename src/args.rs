@@ -1,6 +1,9 @@
+use crate::container_toml::{Build, ContainerToml};
+
 use mmrbi::*;
 
 use std::collections::BTreeSet;
+use std::ffi::OsString;
 
 #[derive(Default)]
 pub struct Args {
@@ -10,10 +13,18 @@ pub struct Args {
     pub tools:      BTreeSet<String>,
     pub allow_sudo: Option<bool>,
     pub skip_install: bool,
+    pub force_generate: bool,
+    pub all:        bool,
+    pub jobs:       usize,
+    pub keep_going: bool,
+    pub deny_warnings: bool,
+    /// Everything after `--`, forwarded to tools via `CARGO_CONTAINER_ARGS`
+    pub passthrough: Vec<String>,
 }
 
 impl Args {
-    pub fn from(mut args: std::env::ArgsOs) -> Self {
+    pub fn from(args: Vec<OsString>) -> Self {
+        let mut args = args.into_iter();
         let mut o = Self::default();
         while let Some(arg) = args.next() {
             let arg = arg.to_string_lossy();
@@ -25,19 +36,136 @@ impl Args {
                 "--allow-sudo"      => o.allow_sudo = Some(true),
                 "--deny-sudo"       => o.allow_sudo = Some(false),
                 "--skip-install"    => o.skip_install = true,
+                "--force-generate"  => o.force_generate = true,
+                "--all"             => o.all = true,
+                "--keep-going"      => o.keep_going = true,
+                "--deny-warnings"   => o.deny_warnings = true,
+                flag @ "--jobs" | flag @ "-j" => {
+                    let jobs = args.next().unwrap_or_else(|| fatal!("expected number of jobs after {}", flag));
+                    let jobs = jobs.to_string_lossy();
+                    o.jobs = jobs.parse().ok().filter(|j| *j > 0).unwrap_or_else(|| fatal!("expected a positive number of jobs after {}, got {:?}", flag, jobs));
+                },
+
+                "--" => {
+                    o.passthrough = args.by_ref().map(|arg| arg.into_string().unwrap_or_else(|arg| fatal!("argument after -- is not valid UTF-8: {:?}", arg))).collect();
+                },
 
                 flag if flag.starts_with("-") => fatal!("unrecognized flag: {}", flag),
                 other => fatal!("unrecognized argument: {}", other),
             }
         }
-        if o.configs.is_empty() { o.configs.insert(String::from("debug")); }
+        if o.jobs == 0 { o.jobs = 1; }
         o
     }
+
+    /// The configs to use for `build`: `--config`s narrow down what the `[[build]]` supports (`*` = everything it supports, or [ContainerToml::configs] if it doesn't say)
+    pub fn build_configs<'a>(&'a self, meta: &'a ContainerToml, build: &'a Build) -> Vec<&'a str> {
+        let configs = match (build.configs.is_empty(), self.configs.is_empty() || self.configs.contains("*")) {
+            (true,  true ) if self.configs.is_empty()   => vec!["debug"],
+            (true,  true )                              => meta.configs(),
+            (true,  false)                              => self.configs.iter().map(|c| c.as_str()).collect(),
+            (false, true )                              => build.configs.iter().map(|c| c.as_str()).collect(),
+            (false, false)                              => build.configs.iter().map(|c| c.as_str()).filter(|c| self.configs.contains(*c)).collect(),
+        };
+        dedup(configs)
+    }
+
+    /// The arches to use for `build` (empty = native), or `None` if `--arch`s rule out everything the `[[build]]` supports
+    pub fn build_arches<'a>(&'a self, build: &'a Build) -> Option<Vec<&'a str>> {
+        let arches = match (build.arches.is_empty(), self.arches.is_empty() || self.arches.contains("*")) {
+            (true,  _    )                              => self.arches.iter().map(|a| a.as_str()).collect::<Vec<_>>(),
+            (false, true )                              => build.arches.iter().map(|a| a.as_str()).collect(),
+            (false, false)                              => build.arches.iter().map(|a| a.as_str()).filter(|a| self.arches.contains(*a)).collect(),
+        };
+        if !build.arches.is_empty() && arches.is_empty() { None } else { Some(dedup(arches)) }
+    }
+
+    /// `--config`s, or just `debug` if none were specified (`*` = [ContainerToml::configs])
+    pub fn configs<'a>(&'a self, meta: &'a ContainerToml) -> Vec<&'a str> {
+        if self.configs.is_empty() { vec!["debug"] }
+        else if self.configs.contains("*") { meta.configs() }
+        else { self.configs.iter().map(|c| c.as_str()).collect() }
+    }
+}
+
+/// Remove repeats (e.g. `configs = ["debug", "debug"]`), keeping the first of each
+fn dedup(mut items: Vec<&str>) -> Vec<&str> {
+    let mut seen = BTreeSet::new();
+    items.retain(|item| seen.insert(*item));
+    items
 }
 
-fn add_arg(o: &mut BTreeSet<String>, flag: &str, param: &str, args: &mut std::env::ArgsOs) {
+fn add_arg(o: &mut BTreeSet<String>, flag: &str, param: &str, args: &mut impl Iterator<Item = OsString>) {
     let next = args.next().unwrap_or_else(|| fatal!("expected {} after {}", param, flag)).to_string_lossy().into_owned();
     if let Some(prev) = o.replace(next) {
         warning!("{} {} was already specified", flag, prev);
     }
 }
+
+#[cfg(test)] fn test_meta(toml: &str) -> ContainerToml {
+    let path = std::env::temp_dir().join(format!("cargo-container-test-{}-{}.toml", std::process::id(), toml.len()));
+    std::fs::write(&path, toml).unwrap();
+    let meta = ContainerToml::from_container_toml(path.as_path()).unwrap();
+    let _ = std::fs::remove_file(&path);
+    meta
+}
+
+#[cfg(test)] fn test_args(args: &[&str]) -> Args { Args::from(args.iter().map(OsString::from).collect()) }
+
+#[test] fn test_build_configs() {
+    let meta = test_meta(r#"
+        [config.profiling]
+        profile = "release"
+
+        [[build]]
+        crates = ["alpha"]
+        tools  = ["platform-console"]
+
+        [[build]]
+        crates  = ["alpha"]
+        tools   = ["platform-console"]
+        configs = ["release", "profiling", "release"]
+    "#);
+    let (unlisted, listed) = (&meta.builds[0], &meta.builds[1]);
+
+    // defaults
+    assert_eq!(test_args(&[]).build_configs(&meta, unlisted), ["debug"]);
+    assert_eq!(test_args(&[]).build_configs(&meta, listed), ["release", "profiling"]);
+    assert_eq!(test_args(&["--config", "*"]).build_configs(&meta, unlisted), ["debug", "release", "profiling"]);
+    assert_eq!(test_args(&["--config", "*"]).build_configs(&meta, listed), ["release", "profiling"]);
+
+    // duplicates
+    assert_eq!(test_args(&["--config", "release", "--config", "release"]).build_configs(&meta, unlisted), ["release"]);
+
+    // narrowing + unknown names
+    assert_eq!(test_args(&["--config", "profiling", "--config", "debug"]).build_configs(&meta, listed), ["profiling"]);
+    assert_eq!(test_args(&["--config", "nope"]).build_configs(&meta, listed), Vec::<&str>::new());
+    assert_eq!(test_args(&["--config", "nope"]).build_configs(&meta, unlisted), ["nope"]); // left for `ContainerToml::config` to reject
+}
+
+#[test] fn test_build_arches() {
+    let meta = test_meta(r#"
+        [[build]]
+        crates = ["alpha"]
+        tools  = ["platform-console"]
+
+        [[build]]
+        crates = ["alpha"]
+        tools  = ["platform-console"]
+        arches = ["x86_64", "wasm32", "x86_64"]
+    "#);
+    let (native, listed) = (&meta.builds[0], &meta.builds[1]);
+
+    // defaults
+    assert_eq!(test_args(&[]).build_arches(native), Some(vec![]));
+    assert_eq!(test_args(&[]).build_arches(listed), Some(vec!["x86_64", "wasm32"]));
+    assert_eq!(test_args(&["--arch", "*"]).build_arches(listed), Some(vec!["x86_64", "wasm32"]));
+
+    // duplicates
+    assert_eq!(test_args(&["--arch", "i686", "--arch", "i686"]).build_arches(native), Some(vec!["i686"]));
+
+    // narrowing + unknown names
+    assert_eq!(test_args(&["--arch", "wasm32"]).build_arches(listed), Some(vec!["wasm32"]));
+    assert_eq!(test_args(&["--arch", "nope"]).build_arches(listed), None);
+    assert_eq!(test_args(&["--arch", "nope"]).build_arches(native), Some(vec!["nope"])); // left for the tool to reject
+}
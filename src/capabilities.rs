@@ -0,0 +1,92 @@
+//! Ask tools what they support (`CARGO_CONTAINER_COMMAND=capabilities`), to skip invocations that would do nothing.
+
+use crate::*;
+
+use mmrbi::*;
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+
+pub use cargo_container_sdk::spec::Capabilities;
+
+
+
+/// `tool`'s capabilities, or `None` if it predates the `capabilities` command (which means "assume anything goes".)
+///
+/// Each executable is only asked once per `cargo container` invocation.  The cache is keyed by the executable `tool`
+/// resolves to on `path`, not by name, as `--all-containers` may find different builds of a tool in each `bin/`.
+pub fn get(tool: &str, path: &OsStr) -> Option<Capabilities> {
+    static CACHE : Mutex<BTreeMap<PathBuf, Option<Capabilities>>> = Mutex::new(BTreeMap::new());
+
+    let exe = find_on_path(path, tool)?; // missing tools are reported when the tool is invoked for real
+    let exe = exe.canonicalize().unwrap_or(exe);
+    if let Some(caps) = CACHE.lock().unwrap().get(&exe) { return caps.clone() }
+    let caps = query(tool, path);
+    CACHE.lock().unwrap().insert(exe, caps.clone());
+    caps
+}
+
+/// Does `tool` predate the `capabilities` handshake (and so perhaps the `CARGO_CONTAINER_DIRECTIVES` side channel too)?
+pub fn legacy(tool: &str, path: &OsStr) -> bool { get(tool, path).map_or(true, |caps| caps.protocol < 1) }
+
+/// Can `tool` run on this host?  `Container.toml`'s `[local-install] tool = { hosts = [...] }` has the final say,
+/// followed by the tool's own capabilities.  Tools limited to other hosts by `Container.toml` aren't even launched.
+pub fn supports_host(meta: &ContainerToml, tool: &str, path: &OsStr) -> bool {
+    let host = std::env::consts::OS;
+    match meta.tool_hosts(tool) {
+        Some(hosts) => hosts.contains(&host),
+        None        => get(tool, path).map_or(true, |caps| caps.supports_host(host)),
+    }
+}
+
+/// Why invoking `command` with `config` and `arches` would do nothing, if `caps` says so
+pub fn unsupported(caps: &Capabilities, command: &str, config: Option<&str>, arches: &str) -> Option<String> {
+    if !caps.supports_command(command) { return Some(format!("doesn't implement `{}`", command)) }
+    if let Some(config) = config {
+        if !caps.supports_config(config) { return Some(format!("doesn't support config `{}`", config)) }
+    }
+    // An empty list or `native` means "native", and `*` means "everything" - only explicit arches can be ruled out
+    let mut arches = arches.split(',').filter(|a| !a.is_empty()).peekable();
+    if arches.peek().is_some() && !arches.clone().any(|a| a == "*" || a == spec::NATIVE_ARCH || caps.supports_arch(a)) {
+        return Some(format!("doesn't support arches `{}`", arches.collect::<Vec<_>>().join(",")));
+    }
+    None
+}
+
+fn query(tool: &str, path: &OsStr) -> Option<Capabilities> {
+    let mut cmd = Command::new(tool);
+    cmd.env("PATH", path);
+    cmd.env(spec::env::COMMAND,             spec::CAPABILITIES);
+    cmd.env(spec::env::PROTOCOL_VERSION,    spec::PROTOCOL_VERSION.to_string());
+    cmd.stdin(Stdio::null()).stderr(Stdio::null());
+
+    // Missing tools, crashes, etc. are reported when the tool is invoked for real
+    let output = cmd.output().ok()?;
+    if !output.status.success() { return None } // e.g. `exit::COMMAND_NOT_IMPLEMENTED` from tools predating `capabilities`
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let json = stdout.lines().rev().find(|line| !line.trim().is_empty())?;
+    let json = serde_json::from_str(json).ok()?; // tools that ignore unknown commands
+
+    let caps = Capabilities::from_json(&json).map_err(|err| warning!(code: tool, "ignoring invalid `capabilities`: {}", err)).ok()?;
+    match caps.protocol.cmp(&spec::PROTOCOL_VERSION) {
+        Ordering::Greater   => warning!(code: tool, "tool speaks protocol version {}, but this cargo-container only speaks version {}: consider updating cargo-container", caps.protocol, spec::PROTOCOL_VERSION),
+        Ordering::Less      => warning!(code: tool, "tool speaks protocol version {}, but this cargo-container speaks version {}: consider updating the tool", caps.protocol, spec::PROTOCOL_VERSION),
+        Ordering::Equal     => {},
+    }
+    Some(caps)
+}
+
+#[test] fn test_unsupported() {
+    let caps = Capabilities::default().commands(&["build", "run"]).arches(&["wasm32"]).configs(&["debug"]);
+    assert_eq!(unsupported(&caps, "build",  Some("debug"),   ""),                None);
+    assert_eq!(unsupported(&caps, "build",  Some("debug"),   "*"),               None);
+    assert_eq!(unsupported(&caps, "build",  Some("debug"),   "x86_64,wasm32"),   None);
+    assert_eq!(unsupported(&caps, "build",  None,            "x86_64"),          Some("doesn't support arches `x86_64`".into()));
+    assert_eq!(unsupported(&caps, "build",  Some("release"), ""),                Some("doesn't support config `release`".into()));
+    assert_eq!(unsupported(&caps, "test",   Some("debug"),   ""),                Some("doesn't implement `test`".into()));
+    assert_eq!(unsupported(&Capabilities::default(), "test", Some("profiling"), "mipsel"), None);
+}
@@ -38,6 +38,61 @@ impl ContainerToml {
         })
     }
 
+    /// Every `Container.toml` in `dir` or its subdirectories (skipping hidden directories and `target`s), sorted by path.
+    pub fn all_under(dir: impl AsRef<Path>) -> io::Result<Vec<Self>> {
+        fn find(dir: &Path, o: &mut Vec<PathBuf>) -> io::Result<()> {
+            let file = dir.join("Container.toml");
+            if file.is_file() { o.push(file) }
+            for entry in std::fs::read_dir(dir).map_err(|err| io::Error::new(err.kind(), format!("unable to read `{}`: {}", dir.display(), err)))? {
+                let entry = entry?;
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                if name.starts_with('.') || name == "target" || !entry.file_type()?.is_dir() { continue }
+                find(&entry.path(), o)?;
+            }
+            Ok(())
+        }
+
+        let mut files = Vec::new();
+        find(dir.as_ref(), &mut files)?;
+        files.sort();
+        files.into_iter().map(Self::from_container_toml).collect()
+    }
+
+    /// Resolve a `[config.name]`, or the built-in `debug` / `release` configs
+    pub fn config(&self, name: &str) -> Option<Config> {
+        if let Some(config) = self.root.config.get(name) { return Some(config.clone()) }
+        match name {
+            "debug"     => Some(Config { profile: Some("dev".into()),     ..Default::default() }),
+            "release"   => Some(Config { profile: Some("release".into()), ..Default::default() }),
+            _           => None,
+        }
+    }
+
+    /// Every config: the built-in `debug` and `release`, followed by any other `[config.name]`s.  This is what `--config *` means.
+    pub fn configs(&self) -> Vec<&str> {
+        let custom = self.root.config.keys().map(|c| c.as_str()).filter(|c| !["debug", "release"].contains(c));
+        ["debug", "release"].iter().copied().chain(custom).collect()
+    }
+
+    /// The host OSes a `[local-install]` tool is limited to by its `hosts = [...]`, if any (as named by `std::env::consts::OS`)
+    pub fn tool_hosts(&self, tool: &str) -> Option<Vec<&str>> {
+        let hosts = self.root.local_install.get(tool)?.get("hosts")?.as_array()?;
+        Some(hosts.iter().filter_map(|h| h.as_str()).collect())
+    }
+
+    /// `[local-install]` for `cargo-local-install`: without tools limited to other hosts, or our own `hosts` keys
+    pub fn host_local_install(&self) -> toml::value::Table {
+        self.root.local_install.iter()
+            .filter(|(tool, _)| self.tool_hosts(tool).map_or(true, |hosts| hosts.contains(&std::env::consts::OS)))
+            .map(|(tool, install)| {
+                let mut install = install.clone();
+                if let toml::Value::Table(install) = &mut install { install.remove("hosts"); }
+                (tool.clone(), install)
+            })
+            .collect()
+    }
+
     pub fn manifest_path(&self) -> &Path {
         self.path.as_path()
     }
@@ -53,7 +108,8 @@ impl ContainerToml {
     }
 
     pub fn resolve_packages(&self) -> io::Result<BTreeMap<package::Name, Package>> {
-        Ok(cargo::Metadata::from_file_workspace(&self.path, self.root.workspace.clone()).packages.iter().map(|p| (p.package.name.clone(), p.clone())).collect())
+        let workspace : cargo::toml::Workspace = toml::Value::Table(self.root.workspace.clone()).try_into().map_err(|err| io::Error::new(io::ErrorKind::InvalidData, format!("invalid `[workspace]`: {}", err)))?;
+        Ok(cargo::Metadata::from_file_workspace(&self.path, workspace).packages.iter().map(|p| (p.package.name.clone(), p.clone())).collect())
     }
 }
 
@@ -74,6 +130,7 @@ pub struct ContainerToml {
 /// ```toml
 /// [local-install]
 /// platform-console = { path = "example/multiplatform/platforms/console" }
+/// platform-windows = { path = "example/multiplatform/platforms/windows", hosts = ["windows"] }  # `hosts` is optional
 /// 
 /// [workspace]
 /// members = [
@@ -92,9 +149,20 @@ pub struct ContainerToml {
 #[non_exhaustive]
 pub struct Root {
     #[serde(default)]                   pub local_install:  toml::value::Table,
-    #[serde(default)]                   pub workspace:      cargo::toml::Workspace,
+    /// Passed through to the generated `Cargo.toml` as-is (+ `.container/crates/*/*` members and `[local-install]` metadata)
+    #[serde(default)]                   pub workspace:      toml::value::Table,
     #[serde(default, rename = "build")] pub builds:         Vec<Build>,
     #[serde(default)]                   pub profile:        toml::value::Table,
+    #[serde(default)]                   pub patch:          toml::value::Table,
+    #[serde(default)]                   pub replace:        toml::value::Table,
+    /// Merged into the generated `.cargo/config.toml`, along with any `cargo-config` directives from tools
+    #[serde(default)]                   pub cargo_config:   toml::value::Table,
+    #[serde(default)]                   pub config:         BTreeMap<String, Config>,
+}
+
+impl Root {
+    /// Keys understood at the root of `Container.toml`
+    pub const KEYS : &'static [&'static str] = &["local-install", "workspace", "build", "profile", "patch", "replace", "cargo-config", "config"];
 }
 
 /// # Example
@@ -103,10 +171,45 @@ pub struct Root {
 /// # [[build]]
 /// crates  = ["alpha", "beta", "delta"]
 /// tools   = ["platform-console"]
+/// configs = ["debug", "release"]  # optional
+/// arches  = ["x86_64", "wasm32"]  # optional
 /// ```
 #[derive(Deserialize)]
 #[non_exhaustive]
 pub struct Build {
     pub crates: Vec<package::Name>,
     pub tools:  Vec<package::Name>,
+    /// Configurations to build by default (`--config` can only narrow these down.)  If empty, `debug` unless otherwise specified.
+    #[serde(default)] pub configs:  Vec<String>,
+    /// Architectures to build by default (`--arch` can only narrow these down.)  If empty, whatever the tool considers native.
+    #[serde(default)] pub arches:   Vec<String>,
+}
+
+impl Build {
+    /// Keys understood in a `[[build]]`
+    pub const KEYS : &'static [&'static str] = &["crates", "tools", "configs", "arches"];
+}
+
+/// # Example
+///
+/// ```toml
+/// # [config.profiling]
+/// profile     = "release-with-debug"  # a cargo profile - `dev`, `release`, or one defined in `[profile]`
+/// features    = ["profiling"]
+/// rustflags   = ["-C", "force-frame-pointers=yes"]
+/// ```
+#[derive(Deserialize, Clone, Default)]
+#[non_exhaustive]
+pub struct Config {
+    #[serde(default)] pub profile:      Option<String>,
+    #[serde(default)] pub features:     Vec<String>,
+    #[serde(default)] pub rustflags:    Vec<String>,
+}
+
+impl Config {
+    /// Keys understood in a `[config.name]`
+    pub const KEYS : &'static [&'static str] = &["profile", "features", "rustflags"];
+
+    /// The cargo profile this config builds with
+    pub fn profile(&self) -> &str { self.profile.as_deref().unwrap_or("dev") }
 }
@@ -0,0 +1,162 @@
+//! Parsing of `cargo-container:...` directives written by tools.
+//!
+//! Two flavors are understood:
+//!
+//! ```text
+//! cargo-container:warning=flat key=value directives (the original format)
+//! cargo-container:json={"version":1,"type":"diagnostic","severity":"warning","message":"..."}
+//! ```
+
+use mmrbi::*;
+
+use std::path::PathBuf;
+
+
+
+pub use cargo_container_sdk::spec::directive::{PREFIX, Diagnostic, Severity};
+use cargo_container_sdk::spec::directive::Directive as Json;
+
+pub enum Directive {
+    /// Run `command` as an administrator (Windows) or root (Linux, OS X, ...)
+    Sudo(String),
+
+    /// `apt-get install -y [package]` as root, deduplicated with other requests
+    AptGetInstall(String),
+
+    /// Display an error, warning, or info message
+    Diagnostic(Diagnostic),
+
+    /// Rerun `generate` if this path changes
+    RerunIfChanged(PathBuf),
+
+    /// Merge these entries into the generated `.cargo/config.toml`
+    CargoConfig(toml::value::Table),
+}
+
+pub trait Report {
+    /// Display this diagnostic as coming from `tool`.
+    fn report(&self, tool: &str);
+}
+
+impl Report for Diagnostic {
+    fn report(&self, tool: &str) {
+        use Severity::*;
+        let code = match self.code.as_ref() {
+            Some(code)  => format!("{}:{}", tool, code),
+            None        => tool.to_string(),
+        };
+        match (self.severity, self.file.as_ref()) {
+            (Error,     None)       => error!  (code: code, "{}", self.message),
+            (Warning,   None)       => warning!(code: code, "{}", self.message),
+            (Info,      None)       => info!   (code: code, "{}", self.message),
+            (Error,     Some(file)) => error!  (at: file, line: self.line, col: self.column, code: code, "{}", self.message),
+            (Warning,   Some(file)) => warning!(at: file, line: self.line, col: self.column, code: code, "{}", self.message),
+            (Info,      Some(file)) => info!   (at: file, line: self.line, col: self.column, code: code, "{}", self.message),
+        }
+    }
+}
+
+impl Directive {
+    /// Parse a line of tool output.
+    ///
+    /// Returns `None` if `line` isn't a directive at all, or `Some(Err(...))` if it looked like one but couldn't be understood.
+    pub fn parse(line: &str) -> Option<Result<Self, String>> {
+        let cc = line.strip_prefix(PREFIX)?;
+        Some(Self::parse_directive(cc))
+    }
+
+    fn parse_directive(cc: &str) -> Result<Self, String> {
+        let diag = |severity, message: &str| Directive::Diagnostic(Diagnostic::new(severity, message));
+
+        if      let Some(json) = cc.strip_prefix("json=")               { Self::parse_json(json) }
+        else if let Some(sudo) = cc.strip_prefix("sudo=")               { Ok(Directive::Sudo(sudo.into())) }
+        else if let Some(pkg)  = cc.strip_prefix("apt-get-install=")    { Ok(Directive::AptGetInstall(pkg.into())) }
+        else if let Some(path) = cc.strip_prefix("rerun-if-changed=")   { Ok(Directive::RerunIfChanged(path.into())) }
+        else if let Some(cfg)  = cc.strip_prefix("cargo-config=")       { toml::from_str(cfg).map(Directive::CargoConfig).map_err(|err| format!("unable to parse cargo-config directive: {}", err)) }
+        else if let Some(msg)  = cc.strip_prefix("error=")              { Ok(diag(Severity::Error,   msg)) }
+        else if let Some(msg)  = cc.strip_prefix("warning=")            { Ok(diag(Severity::Warning, msg)) }
+        else if let Some(msg)  = cc.strip_prefix("info=")               { Ok(diag(Severity::Info,    msg)) }
+        else                                                            { Err(format!("unrecognized directive: {:?}", cc)) }
+    }
+
+    fn parse_json(json: &str) -> Result<Self, String> {
+        let json = serde_json::from_str(json).map_err(|err| err.to_string()).and_then(|json| Json::from_json(&json)).map_err(|err| format!("unable to parse json directive: {}", err))?;
+        Ok(match json {
+            Json::Sudo { command }          => Directive::Sudo(command),
+            Json::AptGetInstall { package } => Directive::AptGetInstall(package),
+            Json::RerunIfChanged { path }   => Directive::RerunIfChanged(path),
+            Json::CargoConfig { config }    => Directive::CargoConfig(toml::Value::try_from(config).map(|config| config.as_table().cloned().unwrap_or_default()).map_err(|err| format!("unable to convert cargo-config directive to TOML: {}", err))?),
+            Json::Diagnostic(d)             => Directive::Diagnostic(d),
+        })
+    }
+}
+
+
+
+#[test] fn test_parse() {
+    assert!(Directive::parse("Compiling alpha v0.0.0").is_none());
+    assert!(Directive::parse("cargo-container:bogus=1").unwrap().is_err());
+
+    match Directive::parse("cargo-container:apt-get-install=libssl-dev") {
+        Some(Ok(Directive::AptGetInstall(pkg))) => assert_eq!(pkg, "libssl-dev"),
+        _ => panic!("expected apt-get-install"),
+    }
+
+    match Directive::parse(r#"cargo-container:json={"version":1,"type":"rerun-if-changed","path":"assets/icon.png"}"#) {
+        Some(Ok(Directive::RerunIfChanged(path))) => assert_eq!(path, PathBuf::from("assets/icon.png")),
+        _ => panic!("expected rerun-if-changed"),
+    }
+
+    match Directive::parse(r#"cargo-container:cargo-config=target.'cfg(windows)'.runner = "wine""#) {
+        Some(Ok(Directive::CargoConfig(config))) => assert_eq!(config["target"]["cfg(windows)"]["runner"].as_str(), Some("wine")),
+        _ => panic!("expected cargo-config"),
+    }
+
+    match Directive::parse(r#"cargo-container:json={"version":1,"type":"diagnostic","severity":"warning","message":"a\nb","file":"src/lib.rs","line":3}"#) {
+        Some(Ok(Directive::Diagnostic(d))) => {
+            assert!(d.severity == Severity::Warning);
+            assert_eq!(d.message, "a\nb");
+            assert_eq!(d.file, Some(PathBuf::from("src/lib.rs")));
+            assert_eq!((d.line, d.column), (3, 0));
+        },
+        _ => panic!("expected diagnostic"),
+    }
+
+    match Directive::parse(r#"cargo-container:json={"version":2,"type":"telemetry"}"#) {
+        Some(Err(err)) => assert!(err.contains("version 2"), "{}", err),
+        _ => panic!("expected error"),
+    }
+}
+
+#[test] fn test_sdk_round_trip() {
+    // What the SDK writes is what the core reads
+    let d = Diagnostic::error("multi\nline").code("E0308").at("src/lib.rs", 3, 5);
+    match Directive::parse(&Json::Diagnostic(d.clone()).to_line()) {
+        Some(Ok(Directive::Diagnostic(parsed))) => assert_eq!(parsed, d),
+        _ => panic!("expected diagnostic"),
+    }
+
+    match Directive::parse(&Json::Sudo { command: "echo hi".into() }.to_line()) {
+        Some(Ok(Directive::Sudo(command))) => assert_eq!(command, "echo hi"),
+        _ => panic!("expected sudo"),
+    }
+
+    match Directive::parse(&Json::AptGetInstall { package: "libssl-dev".into() }.to_line()) {
+        Some(Ok(Directive::AptGetInstall(package))) => assert_eq!(package, "libssl-dev"),
+        _ => panic!("expected apt-get-install"),
+    }
+
+    match Directive::parse(&Json::RerunIfChanged { path: "assets/icon.png".into() }.to_line()) {
+        Some(Ok(Directive::RerunIfChanged(path))) => assert_eq!(path, PathBuf::from("assets/icon.png")),
+        _ => panic!("expected rerun-if-changed"),
+    }
+
+    let config = serde_json::json!({ "target": { "x86_64-pc-windows-gnu": { "runner": "wine", "rustflags": ["-C", "lto"] } } });
+    match Directive::parse(&Json::CargoConfig { config: config.as_object().unwrap().clone() }.to_line()) {
+        Some(Ok(Directive::CargoConfig(parsed))) => {
+            assert_eq!(parsed["target"]["x86_64-pc-windows-gnu"]["runner"].as_str(), Some("wine"));
+            assert_eq!(parsed["target"]["x86_64-pc-windows-gnu"]["rustflags"].as_array().map(|a| a.len()), Some(2));
+        },
+        _ => panic!("expected cargo-config"),
+    }
+}
@@ -1,7 +1,7 @@
 use mmrbi::env;
 
-use std::ffi::OsString;
-use std::path::Path;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
 
 
 
@@ -14,3 +14,9 @@ pub fn prepend_paths<I: IntoIterator<Item = P>, P: AsRef<Path>>(paths: I) -> OsS
     o.push(env::req_var_os("PATH"));
     o
 }
+
+/// Find `exe` (+ `.exe` on windows) in a `PATH`-like list of directories
+pub fn find_on_path(paths: &OsStr, exe: &str) -> Option<PathBuf> {
+    let exe = format!("{}{}", exe, std::env::consts::EXE_SUFFIX);
+    std::env::split_paths(paths).map(|dir| dir.join(&exe)).find(|path| path.is_file())
+}
@@ -0,0 +1,58 @@
+//! Tool exit codes (see `cargo_container_sdk::spec::exit`).
+
+pub use cargo_container_sdk::spec::exit::*;
+
+use std::process::ExitStatus;
+
+
+
+/// The result of a single tool invocation, ordered from least to most severe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Outcome {
+    CommandNotImplemented,
+    PlatformNotImplemented,
+    Success,
+    Warnings,
+    Errors,
+    /// Any other exit code, or termination by signal
+    Crash,
+}
+
+impl Outcome {
+    pub fn from_status(status: ExitStatus) -> Self {
+        match status.code() {
+            Some(SUCCESS)                   => Outcome::Success,
+            Some(ERRORS)                    => Outcome::Errors,
+            Some(WARNINGS)                  => Outcome::Warnings,
+            Some(COMMAND_NOT_IMPLEMENTED)   => Outcome::CommandNotImplemented,
+            Some(PLATFORM_NOT_IMPLEMENTED)  => Outcome::PlatformNotImplemented,
+            _                               => Outcome::Crash,
+        }
+    }
+
+    /// Did the tool actually do something for this command?
+    pub fn ran(self) -> bool { !matches!(self, Outcome::CommandNotImplemented | Outcome::PlatformNotImplemented) }
+
+    /// The exit code for `cargo container` itself, if this was the worst outcome.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Outcome::CommandNotImplemented  => 0,
+            Outcome::PlatformNotImplemented => 0,
+            Outcome::Success                => 0,
+            Outcome::Warnings               => 0,
+            Outcome::Errors                 => 1,
+            Outcome::Crash                  => 2,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Outcome::CommandNotImplemented  => "command not implemented",
+            Outcome::PlatformNotImplemented => "platform not implemented",
+            Outcome::Success                => "success",
+            Outcome::Warnings               => "warnings",
+            Outcome::Errors                 => "errors",
+            Outcome::Crash                  => "crashed",
+        }
+    }
+}
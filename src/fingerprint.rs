@@ -0,0 +1,91 @@
+//! Fingerprints of the inputs to a step, stored as plain text under `.container/fingerprints/` so it can be skipped when nothing changed.
+
+use mmrbi::*;
+
+use std::collections::hash_map::DefaultHasher;
+use std::ffi::OsStr;
+use std::fmt::Write as _;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+
+
+const RERUN_IF_CHANGED : &str = "rerun-if-changed";
+const GENERATED        : &str = "generated";
+
+pub struct Fingerprint {
+    path:   PathBuf,
+    text:   String,
+}
+
+impl Fingerprint {
+    /// Start a new fingerprint to be stored in `.container/fingerprints/{name}.txt`
+    pub fn new(name: &str) -> Self {
+        Self {
+            path:   PathBuf::from(format!(".container/fingerprints/{}.txt", name)),
+            text:   String::from("# Generated by cargo-container\n"),
+        }
+    }
+
+    /// Add an arbitrary `key` / `value` pair
+    pub fn value(&mut self, key: &str, value: impl AsRef<OsStr>) {
+        let _ = writeln!(self.text, "{}\t{}", key, value.as_ref().to_string_lossy());
+    }
+
+    /// Add a hash of the contents of `path`
+    pub fn contents(&mut self, key: &str, path: &Path) {
+        let mut hasher = DefaultHasher::new();
+        match std::fs::read(path) {
+            Ok(bytes)   => { bytes.hash(&mut hasher); self.value(key, format!("{}\t{:016x}", path.display(), hasher.finish())) },
+            Err(_)      => self.value(key, format!("{}\tmissing", path.display())),
+        }
+    }
+
+    /// Add the modified time and size of `path`
+    pub fn file(&mut self, key: &str, path: &Path) {
+        let stamp = std::fs::metadata(path).ok().and_then(|m| {
+            let modified = m.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+            Some(format!("{}.{:09}\t{}", modified.as_secs(), modified.subsec_nanos(), m.len()))
+        });
+        self.value(key, format!("{}\t{}", path.display(), stamp.as_deref().unwrap_or("missing")));
+    }
+
+    /// `true` if the stored fingerprint matches - including any `rerun-if-changed` paths recorded by [Fingerprint::save], and that the `generated` directories still exist
+    pub fn is_fresh(&self) -> bool {
+        let prev = match std::fs::read_to_string(&self.path) {
+            Ok(prev) => prev,
+            Err(_) => return false,
+        };
+        let mut cur = Self { path: self.path.clone(), text: self.text.clone() };
+        for line in prev.lines() {
+            if let Some(path) = line.strip_prefix(RERUN_IF_CHANGED).and_then(|l| l.strip_prefix('\t')).and_then(|l| l.split('\t').next()) {
+                cur.file(RERUN_IF_CHANGED, Path::new(path));
+            } else if let Some(dir) = line.strip_prefix(GENERATED).and_then(|l| l.strip_prefix('\t')) {
+                if Path::new(dir).is_dir() { cur.value(GENERATED, dir) }
+            }
+        }
+        cur.text == prev
+    }
+
+    /// Forget the stored fingerprint (e.g. before running the step, in case it fails midway)
+    pub fn remove(&self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+
+    /// The `generated` directories recorded by the stored fingerprint
+    pub fn generated(&self) -> Vec<PathBuf> {
+        let prev = std::fs::read_to_string(&self.path).unwrap_or_default();
+        prev.lines().filter_map(|line| line.strip_prefix(GENERATED)?.strip_prefix('\t')).map(PathBuf::from).collect()
+    }
+
+    /// Store the fingerprint, along with the current state of the `rerun-if-changed` paths, and the directories the step `generated`
+    pub fn save(mut self, rerun_if_changed: impl Iterator<Item = PathBuf>, generated: impl Iterator<Item = PathBuf>) {
+        for path in rerun_if_changed { self.file(RERUN_IF_CHANGED, &path); }
+        for dir in generated { self.value(GENERATED, &dir); }
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).unwrap_or_else(|err| fatal!("unable to create `{}`: {}", dir.display(), err));
+        }
+        std::fs::write(&self.path, self.text.as_bytes()).unwrap_or_else(|err| fatal!("unable to write `{}`: {}", self.path.display(), err));
+    }
+}
@@ -13,6 +13,8 @@ use std::process::Command;
 
 
 
+const WARNING_COMMENT : &str = "# DO NOT EDIT BY HAND - AUTOGENERATED BY cargo-container FROM Container.toml";
+
 pub fn dot_container(meta: &ContainerToml) -> PathBuf {
     let path = meta.root_directory().join(".container");
     match std::fs::create_dir(&path) {
@@ -29,53 +31,59 @@ pub fn dot_container(meta: &ContainerToml) -> PathBuf {
     path
 }
 
-pub fn workspace_toml(meta: &ContainerToml) {
-    const WARNING_COMMENT : &'static str = "# DO NOT EDIT BY HAND - AUTOGENERATED BY cargo-container FROM Container.toml";
+/// `true` if `path` exists (and was generated by us), `false` if it doesn't exist.  Dies if it exists but wasn't generated by us.
+fn check_generated(path: &Path) -> bool {
+    match is_generated(path) {
+        Some(false) => fatal!("unable to overwrite `{}`: missing expected warning comment: `{}`", path.display(), WARNING_COMMENT),
+        Some(true)  => true,
+        None        => false,
+    }
+}
 
-    let path = meta.root_directory().join("Cargo.toml");
-    match std::fs::File::open(&path) {
+/// `Some(true)` if `path` starts with [WARNING_COMMENT], `Some(false)` if it was written by hand, or `None` if it doesn't exist
+fn is_generated(path: &Path) -> Option<bool> {
+    match std::fs::File::open(path) {
         Ok(file) => {
             let file = io::BufReader::new(file);
             let first_line = file.lines().next().unwrap_or(Ok(String::new())).unwrap_or(String::new());
-            if first_line != WARNING_COMMENT { fatal!("unable to overwrite `{}`: missing expected warning comment: `{}`", path.display(), WARNING_COMMENT) }
+            Some(first_line == WARNING_COMMENT)
         },
-        Err(err) if err.kind() == io::ErrorKind::NotFound => {},
+        Err(err) if err.kind() == io::ErrorKind::NotFound => None,
         Err(err) => fatal!("unable to check `{}`: {}", path.display(), err),
     }
+}
 
-    wimw(path, |o| {
-        writeln!(o, "{}", WARNING_COMMENT)?;
-        writeln!(o)?;
+pub fn workspace_toml(meta: &ContainerToml) {
+    let path = meta.root_directory().join("Cargo.toml");
+    check_generated(&path);
 
-        if !meta.local_install.is_empty() {
-            writeln!(o, "[workspace.metadata.local-install]")?;
-            for (k, v) in meta.local_install.iter() {
-                writeln!(o, "{} = {}", toml::to_string(k).unwrap(), toml_util::to_string_single_line(v))?;
-            }
-            writeln!(o)?;
+    let mut workspace = meta.workspace.clone();
+    match workspace.entry("members").or_insert_with(|| toml::Value::Array(Vec::new())) {
+        toml::Value::Array(members) => members.push(".container/crates/*/*".into()),
+        _ => fatal!("`[workspace] members` must be an array"),
+    }
+    let local_install = meta.host_local_install();
+    if !local_install.is_empty() {
+        match workspace.entry("metadata").or_insert_with(|| toml::Value::Table(Default::default())) {
+            toml::Value::Table(metadata) => { metadata.insert("local-install".into(), toml::Value::Table(local_install)); },
+            _ => fatal!("`[workspace] metadata` must be a table"),
         }
+    }
 
-        writeln!(o, "[workspace]")?;
-        writeln!(o, "members = [")?;
-        for member in meta.workspace.members.iter() {
-            writeln!(o, "    {},", toml::to_string(member).unwrap())?;
-        }
-        writeln!(o, "    {:?}", ".container/crates/*/*")?;
-        writeln!(o, "]")?;
-        writeln!(o, "exclude = [")?;
-        for exclude in meta.workspace.exclude.iter() {
-            writeln!(o, "    {},", toml::to_string(exclude).unwrap())?;
-        }
-        writeln!(o, "]")?;
+    let mut sections = Vec::new();
+    for (key, table) in [("workspace", &workspace), ("profile", &meta.profile), ("patch", &meta.patch), ("replace", &meta.replace)] {
+        if table.is_empty() { continue }
+        let mut section = toml::value::Table::new();
+        section.insert(key.into(), toml::Value::Table(table.clone()));
+        sections.push(toml::to_string(&section).unwrap_or_else(|err| fatal!("unable to generate `[{}]` for `{}`: {}", key, path.display(), err)));
+    }
 
-        if !meta.profile.is_empty() {
+    wimw(path, |o| {
+        writeln!(o, "{}", WARNING_COMMENT)?;
+        for section in sections.iter() {
             writeln!(o)?;
-            writeln!(o, "[profile]")?;
-            for (k, v) in meta.profile.iter() {
-                writeln!(o, "{} = {}", toml::to_string(k).unwrap(), toml_util::to_string_single_line(v))?;
-            }
+            write!(o, "{}", section)?;
         }
-
         Ok(())
     }).or_die();
 
@@ -104,29 +112,302 @@ pub fn workspace_toml(meta: &ContainerToml) {
     }).or_die();
 }
 
-pub fn crates(meta: &ContainerToml) {
+/// Generate crates with every tool, returning the tools whose `generate` failed (only possible with `--keep-going`.)
+pub fn crates(meta: &ContainerToml, args: &Args, manifest: &manifest::Packages) -> BTreeSet<String> {
     let packages = meta.resolve_packages().unwrap_or_else(|err| fatal!("unable to resolve packages: {}", err));
 
-    let mut gen = BTreeMap::<package::Name, BTreeSet<package::Name>>::new();
+    // Generate every crate, but only for the configs and arches that later commands will use
+    let mut gen = BTreeMap::<package::Name, Gen>::new();
     for build in meta.builds.iter() {
+        let configs = args.build_configs(meta, build);
+        let arches = args.build_arches(build);
         for tool in build.tools.iter() {
             let gen = gen.entry(tool.clone()).or_default();
-            for krate in build.crates.iter() {
-                gen.insert(krate.clone());
+            gen.crates.extend(build.crates.iter().cloned());
+            gen.configs.extend(configs.iter().copied());
+            match arches.as_deref() {
+                Some([]) => gen.native = true,
+                arches   => gen.arches.extend(arches.into_iter().flatten().copied()),
             }
         }
     }
+    let gen = gen.into_iter().collect::<Vec<_>>();
+    prune(&gen[..]);
 
     let path = prepend_paths(Some("bin"));
+    let runner = invoke::Jobs::new(args.jobs);
+
+    // Don't bother invoking tools that can't run here, or have told us they don't generate anything
+    let generators = gen.iter()
+        .filter(|(tool, _)| capabilities::supports_host(meta, tool.as_str(), &path))
+        .filter(|(tool, _)| capabilities::get(tool.as_str(), &path).map_or(true, |caps| caps.supports_command("generate")))
+        .collect::<Vec<_>>();
 
-    for (tool, crates) in gen.iter() {
+    let mut failed = BTreeSet::new();
+    runner.run(&generators[..], |&(tool, gen)| {
         let mut cmd = Command::new(tool.as_str());
-        cmd.env("PATH", &path);
-        cmd.env("CARGO_CONTAINER_COMMAND",      "generate");
-        cmd.env("CARGO_CONTAINER_CRATES_DIR",   format!(".container/crates/{}", tool));
-        cmd.env("CARGO_CONTAINER_CONFIGS",      "debug,release"); // XXX
-        gather_crates(&mut cmd, meta, &packages, crates.iter());
-        cmd.status0().unwrap_or_else(|err| fatal!("`{}` generate failed: {}", tool, err));
+        cmd.env("PATH",                &path);
+        cmd.env(spec::env::COMMAND,    "generate");
+        cmd.env(spec::env::CRATES_DIR, format!(".container/crates/{}", tool));
+        cmd.env(spec::env::ARCHES,     gen.arches());
+        invoke::configs(&mut cmd, meta, &gen.configs.iter().copied().collect::<Vec<_>>());
+        gather_crates(&mut cmd, meta, &packages, gen.crates.iter());
+        manifest.set(&mut cmd, &format!("{}-generate", tool), gen.crates.iter().map(|c| c.as_str()));
+
+        let mut fingerprint = Fingerprint::new(&format!("generate-{}", tool));
+        fingerprint.contents("container-toml", meta.manifest_path());
+        fingerprint.contents("manifest", Path::new(&format!(".container/manifests/{}-generate.json", tool)));
+        match find_on_path(&path, tool.as_str()) {
+            Some(exe)   => fingerprint.file("tool", &exe),
+            None        => fingerprint.value("tool", "missing"),
+        }
+        let mut envs = cmd.get_envs().collect::<Vec<_>>();
+        envs.sort();
+        for (k, v) in envs { fingerprint.value("env", format!("{}={}", k.to_string_lossy(), v.unwrap_or_default().to_string_lossy())); }
+        if !args.force_generate && fingerprint.is_fresh() && Path::new(&format!(".container/crates/{}", tool)).exists() {
+            return (fingerprint, None);
+        }
+        fingerprint.remove();
+        stash(tool.as_str());
+
+        runner.configure(&mut cmd);
+        (fingerprint, Some(invoke::tool(cmd, tool, "generate", runner.parallel(), capabilities::legacy(tool, &path))))
+    }, |_, &(tool, _), (fingerprint, invocation)| {
+        let invocation = match invocation {
+            Some(invocation) => invocation,
+            None => return, // fresh
+        };
+        let _ = io::stderr().write_all(&invocation.output[..]);
+        let mut rerun_if_changed = Vec::new();
+        let mut cargo_config = toml::value::Table::new();
+        let mut warnings = 0;
+        for directive in invocation.directives {
+            match directive {
+                Ok(Directive::Diagnostic(diag)) => {
+                    if diag.severity == directive::Severity::Warning { warnings += 1 }
+                    diag.report(tool);
+                },
+                Ok(Directive::RerunIfChanged(path)) => rerun_if_changed.push(path),
+                Ok(Directive::CargoConfig(config)) => merge_cargo_config(&mut cargo_config, config, tool, ""),
+                Ok(_)   => warning!(code: tool, "ignoring directive: only diagnostics, `rerun-if-changed`, and `cargo-config` are supported by `generate`"),
+                Err(err) => warning!(code: tool, "{}", err),
+            }
+        }
+        let mut outcome = exit::Outcome::from_status(invocation.status);
+        let denied = args.deny_warnings && outcome < exit::Outcome::Errors && (outcome == exit::Outcome::Warnings || warnings > 0);
+        if denied { outcome = exit::Outcome::Errors }
+        match (outcome, invocation.status.code()) {
+            (exit::Outcome::Success, _) | (exit::Outcome::Warnings, _) => {
+                let path = PathBuf::from(format!(".container/cargo-config/{}.toml", tool));
+                if cargo_config.is_empty() {
+                    let _ = std::fs::remove_file(&path);
+                } else {
+                    std::fs::create_dir_all(".container/cargo-config").or_die();
+                    let cargo_config = toml::to_string(&cargo_config).unwrap_or_else(|err| fatal!("unable to write `{}`: {}", path.display(), err));
+                    wimw(&path, |o| o.write_all(cargo_config.as_bytes())).or_die();
+                }
+                fingerprint.save(rerun_if_changed.into_iter(), unstash(tool.as_str()).into_iter());
+            },
+            (_, code) => {
+                restore(tool.as_str());
+                let why = match code {
+                    _ if denied => String::from("warnings denied by `--deny-warnings`"),
+                    Some(n)     => format!("exit code {}", n),
+                    None        => String::from("signal"),
+                };
+                if !args.keep_going { fatal!("`{}` generate failed ({})", tool, why) }
+                error!("`{}` generate failed ({})", tool, why);
+                failed.insert(tool.to_string());
+            },
+        }
+    });
+
+    cargo_config(meta, gen.iter().map(|(tool, _)| tool.as_str()));
+    failed
+}
+
+/// Generate `.cargo/config.toml` from `Container.toml`'s `[cargo-config]` and `tools`' `cargo-config` directives
+fn cargo_config<'t>(meta: &ContainerToml, tools: impl Iterator<Item = &'t str>) {
+    let path = meta.root_directory().join(".cargo/config.toml");
+
+    let mut config = toml::value::Table::new();
+    for tool in tools {
+        let tool_path = format!(".container/cargo-config/{}.toml", tool);
+        let tool_config = match std::fs::read_to_string(&tool_path) {
+            Ok(text) => toml::from_str(&text).unwrap_or_else(|err| fatal!("unable to parse `{}`: {}", tool_path, err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => fatal!("unable to read `{}`: {}", tool_path, err),
+        };
+        merge_cargo_config(&mut config, tool_config, tool, "");
+    }
+    // `Container.toml` has the final say
+    merge_cargo_config(&mut config, meta.cargo_config.clone(), "", "");
+
+    if config.is_empty() {
+        // Leave hand written configs alone
+        if is_generated(&path) == Some(true) { std::fs::remove_file(&path).unwrap_or_else(|err| fatal!("unable to remove `{}`: {}", path.display(), err)) }
+        return;
+    }
+    check_generated(&path);
+
+    if meta.root_directory().join(".cargo/config").exists() {
+        warning!("`.cargo/config` exists alongside the generated `.cargo/config.toml`, so cargo will ignore the latter.  Consider moving its contents into `[cargo-config]` in `Container.toml`.");
+    }
+
+    let text = toml::to_string(&config).unwrap_or_else(|err| fatal!("unable to generate `{}`: {}", path.display(), err));
+    meta.create_dir_all(".cargo");
+    wimw(path, |o| {
+        writeln!(o, "{}", WARNING_COMMENT)?;
+        writeln!(o)?;
+        write!(o, "{}", text)?;
+        Ok(())
+    }).or_die();
+}
+
+/// Merge `src` into `dst` like cargo merges config files: tables merge, arrays concatenate, and other values from `src` win.
+/// Conflicting values from a `tool` (as opposed to `Container.toml`, `tool == ""`) are warned about.
+fn merge_cargo_config(dst: &mut toml::value::Table, src: toml::value::Table, tool: &str, prefix: &str) {
+    for (key, value) in src {
+        let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        match (dst.get_mut(&key), value) {
+            (Some(toml::Value::Table(dst)), toml::Value::Table(src)) => merge_cargo_config(dst, src, tool, &path),
+            (Some(toml::Value::Array(dst)), toml::Value::Array(src)) => dst.extend(src),
+            (Some(prev), value) => {
+                if !tool.is_empty() && *prev != value { warning!(code: tool, "`cargo-config` overrides `{}` = {} with {}", path, prev, value) }
+                *prev = value;
+            },
+            (None, value) => { dst.insert(key, value); },
+        }
+    }
+}
+
+/// Delete the generated crates of tools no longer listed in any `[[build]]`.
+/// Stale crates of tools that are still listed are removed when the tool regenerates (see [unstash].)
+fn prune(gen: &[(package::Name, Gen)]) {
+    for tool in subdirs(Path::new(".container/crates")) {
+        if tool == "zzz" { continue } // stub
+        if gen.iter().any(|(t, _)| t.as_str() == tool) { continue }
+
+        let fingerprint = format!(".container/fingerprints/generate-{}.txt", tool);
+        let generated = Fingerprint::new(&format!("generate-{}", tool)).generated();
+        let tool_dir = Path::new(".container/crates").join(&tool);
+        std::fs::remove_dir_all(&tool_dir).unwrap_or_else(|err| fatal!("unable to remove stale generated crates `{}`: {}", tool_dir.display(), err));
+        let _ = std::fs::remove_file(fingerprint);
+        let _ = std::fs::remove_file(format!(".container/cargo-config/{}.toml", tool));
+        if generated.is_empty() {
+            status!("Removed", "stale generated crates `{}`", tool_dir.display());
+        } else {
+            for dir in generated { status!("Removed", "stale generated crate `{}`", dir.display()) }
+        }
+    }
+}
+
+/// Move `tool`'s previously generated crates to `.container/stale/{tool}` before regenerating, so [unstash] can tell which crates this run produced.
+fn stash(tool: &str) {
+    let crates  = Path::new(".container/crates").join(tool);
+    let stale   = Path::new(".container/stale").join(tool);
+    let _ = std::fs::remove_dir_all(&stale); // leftovers of an interrupted run
+    if !crates.exists() { return }
+    std::fs::create_dir_all(".container/stale").or_die();
+    std::fs::rename(&crates, &stale).unwrap_or_else(|err| fatal!("unable to move `{}` to `{}`: {}", crates.display(), stale.display(), err));
+}
+
+/// After `tool` generated successfully, delete (and report) the stashed crates it didn't produce again, and return the ones it did.
+/// Unchanged files are moved back from the stash, so cargo doesn't consider them modified.
+fn unstash(tool: &str) -> Vec<PathBuf> {
+    let crates  = Path::new(".container/crates").join(tool);
+    let stale   = Path::new(".container/stale").join(tool);
+    let generated = subdirs(&crates);
+    for krate in generated.iter() { keep_unchanged(&crates.join(krate), &stale.join(krate)) }
+    for krate in subdirs(&stale) {
+        if generated.contains(&krate) { continue }
+        status!("Removed", "stale generated crate `{}`", crates.join(krate).display());
+    }
+    let _ = std::fs::remove_dir_all(&stale);
+    let _ = std::fs::remove_dir(".container/stale"); // if empty
+    generated.into_iter().map(|krate| crates.join(krate)).collect()
+}
+
+/// After `tool` failed to generate, put its previously generated crates back
+fn restore(tool: &str) {
+    let crates  = Path::new(".container/crates").join(tool);
+    let stale   = Path::new(".container/stale").join(tool);
+    if !stale.exists() { return }
+    let _ = std::fs::remove_dir_all(&crates);
+    std::fs::rename(&stale, &crates).unwrap_or_else(|err| fatal!("unable to move `{}` back to `{}`: {}", stale.display(), crates.display(), err));
+    let _ = std::fs::remove_dir(".container/stale"); // if empty
+}
+
+/// Replace files under `new` with their identical counterparts under `old` (preserving their modified times)
+fn keep_unchanged(new: &Path, old: &Path) {
+    for e in std::fs::read_dir(new).into_iter().flatten().flatten() {
+        let (new, old) = (e.path(), old.join(e.file_name()));
+        match e.file_type() {
+            Ok(t) if t.is_dir() => keep_unchanged(&new, &old),
+            Ok(t) if t.is_file() && std::fs::read(&old).ok().is_some_and(|o| std::fs::read(&new).ok() == Some(o)) => { let _ = std::fs::rename(&old, &new); },
+            _ => {},
+        }
+    }
+}
+
+fn subdirs(path: &Path) -> Vec<String> {
+    std::fs::read_dir(path).into_iter().flatten().flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .collect()
+}
+
+/// Check the generated workspace still loads, moving any tool's crates that break it to `.container/broken/{tool}`, and return `cargo metadata`.
+///
+/// Before (re)generating crates, broken crates are only warned about, since they're about to be regenerated anyways.
+pub fn check_workspace(args: &Args, generated: bool) -> serde_json::Value {
+    let mut broken = Vec::new();
+    let metadata = loop {
+        let output = Command::new("cargo").args(["metadata", "--format-version", "1", "--no-deps"]).stdin(std::process::Stdio::null()).output()
+            .unwrap_or_else(|err| fatal!("unable to run `cargo metadata`: {}", err));
+        if output.status.success() { break serde_json::from_slice(&output.stdout[..]).unwrap_or_else(|err| fatal!("unable to parse `cargo metadata`: {}", err)) }
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        let sep = std::path::MAIN_SEPARATOR;
+        let culprits = std::fs::read_dir(".container/crates").into_iter().flatten().flatten()
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .filter(|tool| tool != "zzz" && stderr.contains(&format!(".container{}crates{}{}{}", sep, sep, tool, sep)))
+            .collect::<Vec<_>>();
+        if culprits.is_empty() { fatal!("generated `Cargo.toml` workspace is broken:\n{}", stderr.trim_end()) }
+
+        for tool in culprits {
+            let from = Path::new(".container/crates").join(&tool);
+            let to = Path::new(".container/broken").join(&tool);
+            let _ = std::fs::remove_dir_all(&to);
+            std::fs::create_dir_all(".container/broken").or_die();
+            std::fs::rename(&from, &to).unwrap_or_else(|err| fatal!("unable to move `{}` to `{}`: {}", from.display(), to.display(), err));
+            let _ = std::fs::remove_file(format!(".container/fingerprints/generate-{}.txt", tool));
+            if generated {
+                error!(code: tool.as_str(), "generated crates broke the workspace, and were moved to `{}`:\n{}", to.display(), stderr.trim_end());
+            } else {
+                warning!(code: tool.as_str(), "previously generated crates broke the workspace, and were moved to `{}` before regenerating:\n{}", to.display(), stderr.trim_end());
+            }
+            broken.push(tool);
+        }
+    };
+    if generated && !broken.is_empty() && !args.keep_going { std::process::exit(1) }
+    metadata
+}
+
+#[derive(Default)]
+struct Gen<'a> {
+    crates:     BTreeSet<package::Name>,
+    configs:    BTreeSet<&'a str>,
+    arches:     BTreeSet<&'a str>,
+    /// Some build wants whatever the tool considers native
+    native:     bool,
+}
+
+impl Gen<'_> {
+    /// [spec::env::ARCHES]: blank if every build is native, otherwise explicit arches (+ [spec::NATIVE_ARCH] if any build is native)
+    fn arches(&self) -> String {
+        let native = Some(spec::NATIVE_ARCH).filter(|_| self.native && !self.arches.is_empty());
+        native.into_iter().chain(self.arches.iter().copied()).collect::<Vec<_>>().join(",")
     }
 }
 
@@ -141,11 +422,47 @@ fn gather_crates<'p>(cmd: &mut Command, meta: &ContainerToml, packages: &BTreeMa
             path.push(c);
         }
 
-        cmd.env(format!("CARGO_CONTAINER_PACKAGE_{}_PATH",          file.package.name), path);
-        cmd.env(format!("CARGO_CONTAINER_PACKAGE_{}_VERSION",       file.package.name), file.package.version.as_str());
-        cmd.env(format!("CARGO_CONTAINER_PACKAGE_{}_DESCRIPTION",   file.package.name), file.package.description.as_ref().map_or("", |d| d.as_str()));
+        cmd.env(spec::env::package_path(&file.package.name),           path);
+        cmd.env(spec::env::package_version(&file.package.name),        file.package.version.as_str());
+        cmd.env(spec::env::package_description(&file.package.name),    file.package.description.as_ref().map_or("", |d| d.as_str()));
         if !o.is_empty() { o.push(',') }
-        o.push_str(&file.package.name.as_str());
+        o.push_str(file.package.name.as_str());
     }
-    cmd.env("CARGO_CONTAINER_PACKAGES", o);
+    cmd.env(spec::env::PACKAGES,   o);
+}
+
+#[test] fn test_merge_cargo_config() {
+    fn table(toml: &str) -> toml::value::Table { toml::from_str(toml).unwrap() }
+
+    let mut config = toml::value::Table::new();
+    merge_cargo_config(&mut config, table(r#"
+        build.rustflags = ["-C", "target-cpu=native"]
+        target.x86_64-pc-windows-gnu.runner = "wine"
+        target.x86_64-pc-windows-gnu.linker = "x86_64-w64-mingw32-gcc"
+    "#), "platform-windows", "");
+    merge_cargo_config(&mut config, table(r#"
+        build.rustflags = ["-C", "force-frame-pointers=yes"]
+        target.x86_64-pc-windows-gnu.runner = "wine64"
+        target.wasm32-unknown-unknown.runner = "wasm-bindgen-test-runner"
+    "#), "platform-web", "");
+    // `[cargo-config]` has the final say
+    merge_cargo_config(&mut config, table(r#"
+        target.x86_64-pc-windows-gnu.runner = "my-wine"
+        net.offline = true
+    "#), "", "");
+
+    assert_eq!(config, table(r#"
+        build.rustflags = ["-C", "target-cpu=native", "-C", "force-frame-pointers=yes"]
+        target.x86_64-pc-windows-gnu.runner = "my-wine"
+        target.x86_64-pc-windows-gnu.linker = "x86_64-w64-mingw32-gcc"
+        target.wasm32-unknown-unknown.runner = "wasm-bindgen-test-runner"
+        net.offline = true
+    "#));
+}
+
+#[test] fn test_gen_arches() {
+    let gen = |native, arches: &[&'static str]| Gen { native, arches: arches.iter().copied().collect(), ..Default::default() }.arches();
+    assert_eq!(gen(true,  &[]),                     "");
+    assert_eq!(gen(false, &["x86_64", "wasm32"]),   "wasm32,x86_64");
+    assert_eq!(gen(true,  &["x86_64", "wasm32"]),   "native,wasm32,x86_64");
 }
@@ -0,0 +1,197 @@
+use crate::*;
+
+use mmrbi::*;
+
+use std::io::{self, BufRead, BufReader, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Stdio};
+use std::sync::{Arc, Mutex, mpsc};
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+
+
+
+/// Environment variable pointing tools at a file to append directives to, instead of mixing them into stdout.
+pub const DIRECTIVES_ENV : &str = spec::env::DIRECTIVES;
+
+pub struct Invocation {
+    pub status:     ExitStatus,
+    pub directives: Vec<Result<Directive, String>>,
+    /// Combined stdout + stderr, if the tool was invoked with `buffer`
+    pub output:     Vec<u8>,
+}
+
+/// Set `CARGO_CONTAINER_CONFIGS`, and describe each config's profile, features, and rustflags to the tool.
+pub fn configs(cmd: &mut Command, meta: &ContainerToml, configs: &[&str]) {
+    cmd.env(spec::env::CONFIGS, configs.join(","));
+    for name in configs.iter().copied() {
+        let config = meta.config(name).unwrap_or_else(|| fatal!("unknown config `{}`: expected `debug`, `release`, or a `[config.{}]` in Container.toml", name, name));
+        cmd.env(spec::env::config_profile(name),    config.profile());
+        cmd.env(spec::env::config_features(name),   config.features.join(","));
+        cmd.env(spec::env::config_rustflags(name),  config.rustflags.join(&spec::env::RUSTFLAGS_SEPARATOR.to_string()));
+    }
+}
+
+/// Spawn a tool and collect any directives it writes.
+///
+/// Directives are read from the `CARGO_CONTAINER_DIRECTIVES` side channel file, and stdout and stderr are inherited,
+/// so tools see a terminal (colors, `\r` progress, ...) just as if they'd been run directly.  For `legacy` tools
+/// (those predating the `capabilities` handshake, which may not know about the side channel) stdout is piped instead,
+/// and `cargo-container:...` lines are still parsed as directives, with everything else passed through byte-for-byte.
+/// If `buffer` is set, stdout and stderr are collected into [Invocation::output] instead, for running several tools
+/// at once without interleaving their output.
+pub fn tool(mut cmd: Command, tool: &str, command: &str, buffer: bool, legacy: bool) -> Invocation {
+    let directives_path = directives_path();
+    cmd.env(DIRECTIVES_ENV, &directives_path);
+    cmd.env(spec::env::PROTOCOL_VERSION, spec::PROTOCOL_VERSION.to_string());
+    if !buffer && !legacy {
+        let status = cmd.status().unwrap_or_else(|err| fatal!("`{}` {} failed: {}", tool, command, err));
+        let mut directives = Vec::new();
+        read_directives(&directives_path, tool, &mut directives);
+        return Invocation { status, directives, output: Vec::new() };
+    }
+
+    if std::env::var_os("CARGO_TERM_COLOR").is_none() && io::stdout().is_terminal() {
+        // stdout is piped, so keep tools (and the cargo invocations they make) from deciding they shouldn't color their output
+        cmd.env("CARGO_TERM_COLOR", "always");
+    }
+    cmd.stdout(Stdio::piped());
+    if buffer { cmd.stderr(Stdio::piped()); }
+
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let mut directives = Vec::new();
+    let mut child = cmd.spawn().unwrap_or_else(|err| fatal!("`{}` {} failed: {}", tool, command, err));
+    let stderr = child.stderr.take().map(|stderr| {
+        let output = Arc::clone(&output);
+        std::thread::spawn(move || {
+            let mut stderr = BufReader::new(stderr);
+            let mut line = Vec::new();
+            while let Ok(n) = stderr.read_until(b'\n', &mut line) {
+                if n == 0 { break }
+                output.lock().unwrap().extend_from_slice(&line[..]);
+                line.clear();
+            }
+        })
+    });
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match stdout.read_until(b'\n', &mut line) {
+            Ok(0) => break,                                                 // EOF
+            Err(err) if err.kind() == io::ErrorKind::BrokenPipe => break,   // EPIPE
+            Err(err) => fatal!("error reading stdout from `{}` {}: {}", tool, command, err),
+            Ok(_) => {},
+        }
+
+        let trimmed = line.strip_suffix(b"\n").unwrap_or(&line[..]);
+        let trimmed = trimmed.strip_suffix(b"\r").unwrap_or(trimmed);
+        match std::str::from_utf8(trimmed).ok().filter(|_| legacy).and_then(Directive::parse) {
+            Some(directive) => directives.push(directive),
+            None if buffer => output.lock().unwrap().extend_from_slice(&line[..]),
+            None => {
+                let stdout = io::stdout();
+                let mut stdout = stdout.lock();
+                let _ = stdout.write_all(&line[..]);
+                let _ = stdout.flush();
+            },
+        }
+    }
+
+    let status = child.wait().unwrap_or_else(|err| fatal!("`{}` {} failed: {}", tool, command, err));
+    if let Some(stderr) = stderr { let _ = stderr.join(); }
+    let output = std::mem::take(&mut *output.lock().unwrap());
+    read_directives(&directives_path, tool, &mut directives);
+    Invocation { status, directives, output }
+}
+
+/// Spawn a tool with stdin, stdout, and stderr all inherited, for interactive commands like `run`.
+///
+/// Directives are only read from the `CARGO_CONTAINER_DIRECTIVES` side channel file - stdout belongs to the tool.
+pub fn interactive(mut cmd: Command, tool: &str, command: &str) -> Invocation {
+    let directives_path = directives_path();
+    cmd.env(DIRECTIVES_ENV, &directives_path);
+    cmd.env(spec::env::PROTOCOL_VERSION, spec::PROTOCOL_VERSION.to_string());
+    cmd.stdin(Stdio::inherit()).stdout(Stdio::inherit()).stderr(Stdio::inherit());
+
+    let status = cmd.status().unwrap_or_else(|err| fatal!("`{}` {} failed: {}", tool, command, err));
+    let mut directives = Vec::new();
+    read_directives(&directives_path, tool, &mut directives);
+    Invocation { status, directives, output: Vec::new() }
+}
+
+fn directives_path() -> PathBuf {
+    static INVOCATIONS : AtomicUsize = AtomicUsize::new(0);
+
+    let dir = std::env::current_dir().unwrap_or_else(|err| fatal!("unable to determine current directory: {}", err)).join(".container/directives");
+    std::fs::create_dir_all(&dir).unwrap_or_else(|err| fatal!("unable to create `{}`: {}", dir.display(), err));
+    let path = dir.join(format!("{}-{}.txt", std::process::id(), INVOCATIONS.fetch_add(1, Relaxed)));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn read_directives(path: &Path, tool: &str, directives: &mut Vec<Result<Directive, String>>) {
+    match std::fs::read(path) {
+        Ok(bytes) => {
+            for line in String::from_utf8_lossy(&bytes[..]).lines() {
+                if line.trim().is_empty() { continue }
+                directives.push(Directive::parse(line).unwrap_or_else(|| Err(format!("expected a directive in {}: {:?}", DIRECTIVES_ENV, line))));
+            }
+            let _ = std::fs::remove_file(path);
+        },
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {},
+        Err(err) => warning!(code: tool, "unable to read directives from `{}`: {}", path.display(), err),
+    }
+}
+
+
+
+/// Runs independent jobs, up to `--jobs N` at a time.
+///
+/// When running more than one job at a time, a jobserver is shared with the tools (and the `cargo` processes they
+/// spawn, via `CARGO_MAKEFLAGS`) so `N` is a limit for the whole process tree, rather than for each tool.
+pub struct Jobs {
+    client: Option<jobserver::Client>,
+}
+
+impl Jobs {
+    pub fn new(jobs: usize) -> Self {
+        Self {
+            client: if jobs <= 1 { None } else { Some(jobserver::Client::new(jobs).unwrap_or_else(|err| fatal!("unable to create jobserver: {}", err))) },
+        }
+    }
+
+    /// `true` if jobs may run concurrently, and should buffer their output.
+    pub fn parallel(&self) -> bool { self.client.is_some() }
+
+    /// Share the jobserver (if any) with `cmd`.
+    pub fn configure(&self, cmd: &mut Command) {
+        if let Some(client) = self.client.as_ref() { client.configure(cmd); }
+    }
+
+    /// Call `run` for every item (possibly in parallel), and `done` for every result (on the calling thread, in completion order.)
+    pub fn run<I: Sync, R: Send>(&self, items: &[I], run: impl Fn(&I) -> R + Sync, mut done: impl FnMut(usize, &I, R)) {
+        let client = match self.client.as_ref() {
+            None => {
+                for (i, item) in items.iter().enumerate() { done(i, item, run(item)); }
+                return;
+            },
+            Some(client) => client,
+        };
+
+        std::thread::scope(|scope| {
+            let (send, recv) = mpsc::channel();
+            for (i, item) in items.iter().enumerate() {
+                let send = send.clone();
+                let run = &run;
+                scope.spawn(move || {
+                    let token = client.acquire().unwrap_or_else(|err| fatal!("unable to acquire jobserver token: {}", err));
+                    let r = run(item);
+                    drop(token);
+                    let _ = send.send((i, r));
+                });
+            }
+            drop(send);
+            for (i, r) in recv { done(i, &items[i], r); }
+        });
+    }
+}
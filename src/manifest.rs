@@ -0,0 +1,104 @@
+//! `CARGO_CONTAINER_MANIFEST`: a JSON file describing the packages a tool is being invoked on.
+//!
+//! ```json
+//! { "version": 1, "packages": [ { "name": "alpha", "directory": "apps/alpha", "version": "0.1.0", "authors": [], ... } ] }
+//! ```
+//!
+//! Each package is `cargo metadata`'s description of it (authors, license, edition, features, targets, metadata, ...)
+//! plus a `directory` relative to `Container.toml`, and a `container` object holding `[package.metadata.container]`
+//! with the `icon` path made relative to `Container.toml` as well.  `clean` describes packages with their `Cargo.toml` instead (see [Packages::from_container_toml].)
+
+use crate::*;
+
+use mmrbi::*;
+
+use serde_json::{json, Value};
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+use std::process::Command;
+
+
+
+pub use cargo_container_sdk::spec::manifest::*;
+pub use cargo_container_sdk::spec::env::MANIFEST as MANIFEST_ENV;
+
+/// The workspace's (non-generated) packages, by name
+pub struct Packages(BTreeMap<String, Value>);
+
+impl Packages {
+    /// Collect packages from the output of `cargo metadata --format-version 1`
+    pub fn from_metadata(meta: &ContainerToml, metadata: Value) -> Self {
+        Self::from_packages(meta, metadata.get("packages").and_then(|p| p.as_array()).cloned().unwrap_or_default())
+    }
+
+    /// Collect packages straight from the `Cargo.toml`s of `Container.toml`'s `[workspace]`, without running `cargo metadata`.
+    /// Packages are described by their `[package]` (with `workspace = true` keys resolved, and `version` / `description` always present) and `[features]` tables,
+    /// so they lack the `targets` cargo would discover.
+    /// Used by `clean`, which must work even when the workspace is too broken to load.
+    pub fn from_container_toml(meta: &ContainerToml) -> Self {
+        let packages = meta.resolve_packages().unwrap_or_else(|err| fatal!("unable to resolve packages: {}", err));
+        Self::from_packages(meta, packages.values().filter_map(|package| {
+            let path = package.manifest_path();
+            let text = std::fs::read_to_string(path).map_err(|err| warning!("unable to read `{}`: {}", path.display(), err)).ok()?;
+            let toml = toml::from_str::<toml::Value>(&text).map_err(|err| warning!("unable to parse `{}`: {}", path.display(), err)).ok()?;
+            let mut package = serde_json::to_value(toml.get("package")?).ok()?;
+            // `key.workspace = true` inherits from `[workspace.package]` in `Container.toml`
+            for (key, value) in package.as_object_mut()?.iter_mut() {
+                if value.get("workspace") != Some(&Value::Bool(true)) { continue }
+                *value = meta.workspace.get("package").and_then(|p| p.get(key)).and_then(|v| serde_json::to_value(v).ok()).unwrap_or(Value::Null);
+            }
+            for key in ["version", "description"].iter().copied() {
+                if package.get(key).is_none() { package[key] = Value::Null; }
+            }
+            package["features"] = toml.get("features").and_then(|f| serde_json::to_value(f).ok()).unwrap_or_else(|| json!({}));
+            package["manifest_path"] = path.canonicalize().unwrap_or_else(|_| path.into()).to_string_lossy().into();
+            Some(package)
+        }).collect())
+    }
+
+    fn from_packages(meta: &ContainerToml, all: Vec<Value>) -> Self {
+        let root = meta.root_directory().canonicalize().unwrap_or_else(|_| meta.root_directory().into());
+        let mut packages = BTreeMap::new();
+        for mut package in all {
+            let dir = match package.get("manifest_path").and_then(|p| p.as_str()).and_then(|p| Path::new(p).parent()) {
+                Some(dir) => dir.strip_prefix(&root).unwrap_or(dir).to_path_buf(),
+                None => continue,
+            };
+            if dir.starts_with(".container") { continue } // generated
+            let name = match package.get("name").and_then(|n| n.as_str()) {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+            let dir = dir.components().map(|c| c.as_os_str().to_string_lossy()).collect::<Vec<_>>().join("/");
+            let mut container = match package["metadata"]["container"].clone() {
+                Value::Object(container) => container,
+                _ => Default::default(),
+            };
+            if let Some(icon) = container.get("icon").and_then(|i| i.as_str()) {
+                let icon = if dir.is_empty() { icon.to_string() } else { format!("{}/{}", dir, icon) };
+                container.insert("icon".into(), icon.into());
+            }
+            package["container"] = container.into();
+            package["directory"] = dir.into();
+            packages.insert(name, package);
+        }
+        Self(packages)
+    }
+
+    /// The packages' names
+    pub fn names(&self) -> impl Iterator<Item = &str> { self.0.keys().map(|k| k.as_str()) }
+
+    /// Write a manifest describing `names` to `.container/manifests/{file}.json`, and point `CARGO_CONTAINER_MANIFEST` at it
+    pub fn set<'n>(&self, cmd: &mut Command, file: &str, names: impl Iterator<Item = &'n str>) {
+        let packages = names.map(|name| self.0.get(name).cloned().unwrap_or_else(|| fatal!("package `{}` not found in the workspace", name))).collect::<Vec<_>>();
+        let manifest = serde_json::to_string_pretty(&json!({ "version": VERSION, "packages": packages })).unwrap();
+
+        let dir = std::env::current_dir().unwrap_or_else(|err| fatal!("unable to determine current directory: {}", err)).join(".container/manifests");
+        std::fs::create_dir_all(&dir).unwrap_or_else(|err| fatal!("unable to create `{}`: {}", dir.display(), err));
+        let path = dir.join(format!("{}.json", file));
+        mmrbi::fs::write_if_modified_with(&path, |o| o.write_all(manifest.as_bytes())).or_die();
+        cmd.env(MANIFEST_ENV, &path);
+    }
+}
@@ -2,10 +2,10 @@ use crate::*;
 
 use mmrbi::*;
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::Write as _;
 use std::ffi::*;
-use std::io::{self, BufRead, BufReader};
+use std::io::{self, IsTerminal, Write as _};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
@@ -18,33 +18,65 @@ pub fn run() {
         args = std::env::args_os();
         let _exe = args.next();
     }
+    let mut args = args.collect::<Vec<_>>();
+    let manifest_path   = take_flag(&mut args, "--manifest-path", true);
+    let all_containers  = take_flag(&mut args, "--all-containers", false).is_some();
 
-    let meta = ContainerToml::from_current_dir().unwrap_or_else(|err| fatal!("{}", err));
-    std::env::set_current_dir(meta.root_directory()).unwrap();
-
-    let cmd = args.next().unwrap_or_else(|| fatal!("expected subcommand"));
+    if args.is_empty() { fatal!("expected subcommand") }
+    let cmd = args.remove(0);
     let cmd = cmd.to_string_lossy();
     let cmd = &*cmd;
 
     match cmd {
         // Metadata Commands
-        "help"                  => help(args),
-        "version"               => version(args),
+        "help"                  => return help(args),
+        "version"               => return version(args),
+        _                       => {},
+    }
+
+    let metas = match (manifest_path, all_containers) {
+        (Some(_), true) => fatal!("`--manifest-path` and `--all-containers` cannot be used together"),
+        (Some(path), false) => {
+            let path = Path::new(&path).canonicalize().unwrap_or_else(|err| fatal!("unable to find `--manifest-path {}`: {}", Path::new(&path).display(), err));
+            vec![ContainerToml::from_container_toml(path).unwrap_or_else(|err| fatal!("{}", err))]
+        },
+        (None, true) => {
+            let dir = std::env::current_dir().unwrap_or_else(|err| fatal!("unable to determine the current directory: {}", err));
+            let metas = ContainerToml::all_under(&dir).unwrap_or_else(|err| fatal!("{}", err));
+            if metas.is_empty() { fatal!("`--all-containers`: no Container.toml files found under `{}`", dir.display()) }
+            metas
+        },
+        (None, false) => vec![ContainerToml::from_current_dir().unwrap_or_else(|err| fatal!("{}", err))],
+    };
+
+    for meta in metas.iter() {
+        if metas.len() > 1 {
+            eprintln!();
+            status!("Container", "{}", meta.manifest_path().display());
+        }
+        std::env::set_current_dir(meta.root_directory()).unwrap_or_else(|err| fatal!("unable to enter `{}`: {}", meta.root_directory().display(), err));
+        validate::container_toml(meta);
+        subcommand(meta, cmd, args.clone());
+    }
+}
 
+fn subcommand(meta: &ContainerToml, cmd: &str, args: Vec<OsString>) {
+    match cmd {
         // General Commands
-        "bench"                 => gen_then_fwd(&meta, args, "bench",   false, "Benchmarking"),
-        "build" | "b"           => gen_then_fwd(&meta, args, "build",   false, "Building"),
-        "check" | "c"           => check(&meta, args),
-        "clean"                 => clean(&meta, args),
-        "deploy"                => gen_then_fwd(&meta, args, "deploy",  false, "Deploying"),
-        "doc"                   => gen_then_fwd(&meta, args, "doc",     false, "Documenting"),
-        "fetch"                 => fetch(&meta, args),
-        "fuzz"                  => gen_then_fwd(&meta, args, "fuzz",    false, "Fuzzing"),
-        "package"               => gen_then_fwd(&meta, args, "package", false, "Packaging"),
-        "run" | "r"             => gen_then_fwd(&meta, args, "run",     false, "Running"), // XXX: Is this what we actually want?
-        "setup"                 => setup(&meta, args),
-        "test" | "t"            => gen_then_fwd(&meta, args, "test",    false, "Testing"),
-        "update"                => gen_then_fwd(&meta, args, "update",  false, "Updating"),
+        "bench"                 => gen_then_fwd(meta, args, "bench",   false, "Benchmarking"),
+        "build" | "b"           => gen_then_fwd(meta, args, "build",   false, "Building"),
+        "check" | "c"           => check(meta, args, "check",  "Checking"),
+        "clean"                 => clean(meta, args),
+        "clippy"                => check(meta, args, "clippy", "Linting"),
+        "deploy"                => gen_then_fwd(meta, args, "deploy",  false, "Deploying"),
+        "doc"                   => gen_then_fwd(meta, args, "doc",     false, "Documenting"),
+        "fetch"                 => fetch(meta, args),
+        "fuzz"                  => gen_then_fwd(meta, args, "fuzz",    false, "Fuzzing"),
+        "package"               => gen_then_fwd(meta, args, "package", false, "Packaging"),
+        "run" | "r"             => run_crate(meta, args),
+        "setup"                 => setup(meta, args),
+        "test" | "t"            => gen_then_fwd(meta, args, "test",    false, "Testing"),
+        "update"                => gen_then_fwd(meta, args, "update",  false, "Updating"),
 
         // NYI commands
         "generate-lockfile"     => fatal!("not yet implemented: {}", cmd),
@@ -58,11 +90,21 @@ pub fn run() {
     }
 }
 
-fn help(_args: std::env::ArgsOs) {
+/// Remove `flag` (and its value, if `value`) from anywhere before `--` in `args`, as it applies to all subcommands
+fn take_flag(args: &mut Vec<OsString>, flag: &str, value: bool) -> Option<OsString> {
+    let end = args.iter().position(|arg| arg == "--").unwrap_or(args.len());
+    let i = args[..end].iter().position(|arg| arg == flag)?;
+    args.remove(i);
+    if !value { return Some(OsString::new()) }
+    if i >= end-1 { fatal!("expected a value after {}", flag) }
+    Some(args.remove(i))
+}
+
+fn help(_args: Vec<OsString>) {
     let _ = print_usage(&mut std::io::stdout().lock());
 }
 
-fn version(_args: std::env::ArgsOs) {
+fn version(_args: Vec<OsString>) {
     println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
 }
 
@@ -75,11 +117,12 @@ fn print_usage(o: &mut impl io::Write) -> io::Result<()> {
     writeln!(o, "bench      \"Prepare workspace\" and use `tools` to benchmark the crates")?;
     writeln!(o, "check | c  \"Prepare workspace\" and use `tools` to verify the crates compile")?;
     writeln!(o, "clean      Attempt to get rid of generated files")?;
+    writeln!(o, "clippy     \"Prepare workspace\" and use `tools` to lint the crates for every platform")?;
     writeln!(o, "doc        \"Prepare workspace\" and use `tools` to document the crates")?;
     writeln!(o, "fetch      \"Prepare workspace\" and use `tools` to fetch the crates + `cargo fetch`")?;
     writeln!(o, "fuzz       \"Prepare workspace\" and use `tools` to fuzz-test the crates")?;
     writeln!(o, "package    \"Prepare workspace\" and use `tools` to package the crates")?;
-    writeln!(o, "run   | r  \"Prepare workspace\" and use `tools` to run the crates")?;
+    writeln!(o, "run   | r  \"Prepare workspace\" and use a `tool` to run a single crate")?;
     writeln!(o, "test       \"Prepare workspace\" and use `tools` to test the crates")?;
     writeln!(o, "update     \"Prepare workspace\" and use `tools` to update dependencies")?;
     writeln!(o)?;
@@ -90,46 +133,166 @@ fn print_usage(o: &mut impl io::Write) -> io::Result<()> {
     writeln!(o, "4. Generate a `Cargo.toml` alongside `Container.toml` consuming said crates")?;
     writeln!(o)?;
     writeln!(o, "    Flags:")?;
-    writeln!(o, "--arch     <arch | *>      Specify an architecture to target instead of using the default of 'whatever is native'")?;
-    writeln!(o, "--config   <config | *>    Specify a configuration to target instead of using the default of 'debug'")?;
+    writeln!(o, "--arch     <arch | *>      Specify an architecture to target instead of the `[[build]]`'s `arches` (default: 'whatever is native')")?;
+    writeln!(o, "--config   <config | *>    Specify a configuration to target instead of the `[[build]]`'s `configs` (default: 'debug')")?;
     writeln!(o, "--crate    <crate>         Specify a specific crate to build/run/package instead of selecting all available crates")?;
     writeln!(o, "--tool     <tool>          Specify a specific tool to use instead of selecting all available tools")?;
+    writeln!(o, "--all                      (clean) Also delete `[local-install]`ed tools in `bin/`")?;
+    writeln!(o, "--force-generate           Regenerate crates even if nothing seems to have changed since the last time")?;
+    writeln!(o, "--deny-warnings            Treat warnings reported by tools as errors")?;
+    writeln!(o, "--keep-going               Keep running other tools and configs after one fails, then summarize the results")?;
+    writeln!(o, "--manifest-path <path>     Use the specified `Container.toml` instead of searching the current directory and its parents")?;
+    writeln!(o, "--all-containers           Run the command for every `Container.toml` found under the current directory")?;
+    writeln!(o, "--jobs | -j <N>            Run up to N tool invocations (and the builds they spawn) at once, instead of one at a time")?;
+    writeln!(o, "-- <args...>               Pass the remaining arguments to tools (and the programs they run)")?;
     writeln!(o)?;
     Ok(())
 }
 
-fn check(meta: &ContainerToml, args: std::env::ArgsOs) {
-    gen_then_fwd(&meta, args, "check", true, "Checking");
-    Command::new("cargo").arg("check").status0().or_die();
+/// `check` or `clippy` (`command`) the crates with each tool, then with `cargo {command}` for any tools that didn't
+fn check(meta: &ContainerToml, args: Vec<OsString>, command: &str, verbing: &str) {
+    let args = Args::from(args);
+    let (manifest, generate_failed) = prepare(meta, &args);
+    let unchecked = fwd(meta, &args, &manifest, &generate_failed, command, true, verbing);
+
+    // Tools that don't implement `command` still generated crates for specific targets - check those, instead of for the host
+    let path = prepend_paths(Some("bin"));
+    for Job { tool, config, crates, .. } in unchecked {
+        let packages = crates.iter().filter_map(|c| generated_package(tool, c)).collect::<Vec<_>>();
+        if packages.is_empty() { continue }
+        let targets = capabilities::get(tool, &path).and_then(|caps| caps.targets).map_or(vec![None], |t| t.into_iter().map(Some).collect());
+        let profile = meta.config(config).unwrap_or_else(|| fatal!("unknown config `{}`", config));
+        for target in targets {
+            status!(verbing, "{} | {} | {} crates{}", tool, config, packages.len(), target.as_ref().map_or(String::new(), |t| format!(" for {}", t)));
+            let mut cmd = Command::new("cargo");
+            cmd.arg(command);
+            if let Some(target) = target.as_ref() { cmd.arg("--target").arg(target); }
+            cmd.arg("--profile").arg(profile.profile());
+            if !profile.features.is_empty() { cmd.arg("--features").arg(profile.features.join(",")); }
+            if !profile.rustflags.is_empty() { cmd.env("CARGO_ENCODED_RUSTFLAGS", profile.rustflags.join(&spec::env::RUSTFLAGS_SEPARATOR.to_string())); }
+            for package in packages.iter() { cmd.arg("-p").arg(package); }
+            if command == "clippy" && !args.passthrough.is_empty() { cmd.arg("--").args(&args.passthrough); }
+            cmd.status0().or_die();
+        }
+    }
+
+    // The original packages are (presumably) portable - check them for the host
+    let mut cmd = Command::new("cargo");
+    cmd.arg(command);
+    for name in manifest.names() { cmd.arg("-p").arg(name); }
+    if command == "clippy" && !args.passthrough.is_empty() { cmd.arg("--").args(&args.passthrough); }
+    cmd.status0().or_die();
+}
+
+/// `true` if a tool doesn't implement `command` at all - per its `caps`, or the `outcome` of invoking it - so [check] should fall back on checking its crates itself.
+/// Tools that implement `command` but decline for this platform ([exit::Outcome::PlatformNotImplemented]) are left alone.
+fn not_implemented_by(caps: Option<&capabilities::Capabilities>, command: &str, outcome: Option<exit::Outcome>) -> bool {
+    caps.is_some_and(|caps| !caps.supports_command(command)) || outcome == Some(exit::Outcome::CommandNotImplemented)
 }
 
-fn clean(meta: &ContainerToml, args: std::env::ArgsOs) {
-    // XXX: cleanup bin dir?
+/// The package name of `tool`'s generated crate for `krate` (`.container/crates/{tool}/{krate}/Cargo.toml`), if any
+fn generated_package(tool: &str, krate: &str) -> Option<String> {
+    let path = format!(".container/crates/{}/{}/Cargo.toml", tool, krate);
+    let toml = std::fs::read_to_string(&path).ok()?;
+    match toml::from_str::<toml::Value>(&toml) {
+        Ok(toml) => toml.get("package")?.get("name")?.as_str().map(String::from),
+        Err(err) => { warning!(code: tool, "unable to parse `{}`: {}", path, err); None },
+    }
+}
+
+fn clean(meta: &ContainerToml, args: Vec<OsString>) {
+    let args = Args::from(args);
+    generate::dot_container(meta);
+    local_install(meta, &args);
+    let manifest = manifest::Packages::from_container_toml(meta); // not `cargo metadata`: clean should still work if the workspace is broken
+
+    // Tools clean up any outputs of their own first, for all configs unless specific ones were requested
+    let path = prepend_paths(meta.root_directory().join("bin").canonicalize().ok().map(|bin| bin.cleanup())); // `bin/` may not exist yet (or at all)
+    let configs = if args.configs.is_empty() { meta.configs() } else { args.configs(meta) };
+    let mut tools = BTreeMap::<&str, BTreeSet<&str>>::new();
+    for job in jobs(meta, &args) { tools.entry(job.tool).or_default().extend(job.crates); }
+    let mut skipped = Vec::new();
+    tools.retain(|tool, _| match capabilities::get(tool, &path).and_then(|caps| capabilities::unsupported(&caps, "clean", None, "")) {
+        None => true,
+        Some(reason) => { skipped.push(format!("{} ({})", tool, reason)); false },
+    });
+    if !skipped.is_empty() { status!("Skipping", "{}", skipped.join(", ")); }
+    for (tool, crates) in tools.iter() {
+        status!("Cleaning", "{} | {} | {} crates", tool, configs.join(","), crates.len());
+        let mut cmd = Command::new(tool);
+        cmd.env("PATH",                &path);
+        cmd.env(spec::env::COMMAND,    "clean");
+        cmd.env(spec::env::CRATES_DIR, format!(".container/crates/{}", tool));
+        invoke::configs(&mut cmd, meta, &configs);
+        cmd.env(spec::env::PACKAGES,   crates.iter().copied().collect::<Vec<_>>().join(","));
+        manifest.set(&mut cmd, &format!("{}-clean", tool), crates.iter().copied());
+        cmd.env(spec::env::ARGS,       serde_json::to_string(&args.passthrough).unwrap());
+        cmd.stdin(Stdio::null());
+        let invocation = invoke::tool(cmd, tool, "clean", false, capabilities::legacy(tool, &path));
+        for directive in invocation.directives {
+            match directive {
+                Ok(Directive::Diagnostic(diag)) => diag.report(tool),
+                Ok(_)   => warning!(code: tool, "ignoring directive: only diagnostics are supported by `clean`"),
+                Err(err) => warning!(code: tool, "{}", err),
+            }
+        }
+        match (exit::Outcome::from_status(invocation.status), invocation.status.code()) {
+            (exit::Outcome::Errors, _)      => std::process::exit(1),
+            (exit::Outcome::Crash, Some(n)) => fatal!("`{}` clean failed (exit code {})", tool, n),
+            (exit::Outcome::Crash, None)    => fatal!("`{}` clean failed (signal)", tool),
+            _                               => {},
+        }
+    }
+
+    // `--crate` / `--tool` only clean up after those, leaving everything else alone
+    if !args.crates.is_empty() || !args.tools.is_empty() { return }
+
+    // Generated crates go first, so a broken one can't stop `cargo clean` from loading the workspace (the stub keeps `.container/crates/*/*` matching)
     let dot_container = meta.root_directory().join(".container");
-    Command::new("cargo").arg("clean").args(args).current_dir(meta.root_directory()).status0().unwrap_or_else(|err| fatal!("`cargo clean` failed: {}", err));
+    for e in std::fs::read_dir(dot_container.join("crates")).into_iter().flatten().flatten() {
+        if e.file_name() == "zzz" { continue }
+        std::fs::remove_dir_all(e.path()).unwrap_or_else(|err| fatal!("`cargo container clean` failed to delete `{}`: {}", e.path().display(), err));
+    }
+    Command::new("cargo").arg("clean").current_dir(meta.root_directory()).status0().unwrap_or_else(|err| fatal!("`cargo clean` failed: {}", err));
     std::fs::remove_dir_all(&dot_container).unwrap_or_else(|err| fatal!("`cargo container clean` failed to delete `{}`: {}", dot_container.display(), err));
+
+    if args.all {
+        let bin = meta.root_directory().join("bin");
+        match std::fs::remove_dir_all(&bin) {
+            Ok(()) => {},
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {},
+            Err(err) => fatal!("`cargo container clean --all` failed to delete `{}`: {}", bin.display(), err),
+        }
+    }
 }
 
-fn fetch(meta: &ContainerToml, args: std::env::ArgsOs) {
-    gen_then_fwd(&meta, args, "fetch", true, "Fetching");
+fn fetch(meta: &ContainerToml, args: Vec<OsString>) {
+    gen_then_fwd(meta, args, "fetch", true, "Fetching");
     Command::new("cargo").arg("fetch").status0().or_die();
 }
 
-fn setup(meta: &ContainerToml, args: std::env::ArgsOs) {
-    gen_then_fwd(&meta, args, "setup", true, "Setup");
+fn setup(meta: &ContainerToml, args: Vec<OsString>) {
+    gen_then_fwd(meta, args, "setup", true, "Setup");
 }
 
-fn gen_then_fwd(meta: &ContainerToml, args: std::env::ArgsOs, command: &str, ok_none: bool, verbing: &str) {
-    let args = Args::from(args);
+/// "Prepare workspace" - generate `Cargo.toml`, install `[local-install]` deps, and generate crates.
+///
+/// Also returns the tools whose `generate` failed (only possible with `--keep-going`.)
+fn prepare(meta: &ContainerToml, args: &Args) -> (manifest::Packages, BTreeSet<String>) {
     std::fs::remove_dir_all(".container/scripts/setup").unwrap_or_else(|err| if err.kind() != io::ErrorKind::NotFound { fatal!("unable to remove .container/scripts/setup: {}", err) });
     generate::dot_container(meta);
     generate::workspace_toml(meta);
-    local_install(meta, &args);
-    generate::crates(meta);
-
-    let path = prepend_paths(Some(Path::new("bin").canonicalize().unwrap().cleanup()));
-    let arches = args.arches.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(",");
+    local_install(meta, args);
+    // `generate` needs the manifest, which needs `cargo metadata`, which needs a workspace that loads - so crates broken by a previous run
+    // are moved aside (and warned about) now, instead of only after regenerating them.
+    let manifest = manifest::Packages::from_metadata(meta, generate::check_workspace(args, false));
+    let generate_failed = generate::crates(meta, args, &manifest);
+    generate::check_workspace(args, true);
+    (manifest, generate_failed)
+}
 
+/// Every tool x config combination selected by `args`, and the crates to use them on
+fn jobs<'a>(meta: &'a ContainerToml, args: &'a Args) -> Vec<Job<'a>> {
     for c in args.crates.iter() {
         if !meta.builds.iter().any(|b| b.crates.iter().any(|c2| c.as_str() == c2)) {
             warning!("`--crate {}` is not part of any `[[build]]`'s crates", c);
@@ -142,95 +305,172 @@ fn gen_then_fwd(meta: &ContainerToml, args: std::env::ArgsOs, command: &str, ok_
         }
     }
 
-    let mut apt_packages = BTreeSet::new();
-    let mut sudos = Vec::new();
-
-    let mut builds = ok_none;
+    let path = prepend_paths(Some("bin"));
+    let mut unsupported = BTreeSet::new();
+    let mut jobs = Vec::new();
     for build in meta.builds.iter() {
         let crates = build.crates.iter().map(|c| c.as_str()).filter(|c| args.crates.is_empty() || args.crates.contains(*c)).collect::<Vec<_>>();
         if crates.is_empty() { continue }
-        let crates_s = crates.join(",");
+
+        let configs = args.build_configs(meta, build);
+        let arches = match args.build_arches(build) { Some(arches) => arches.join(","), None => continue };
+
         for tool in build.tools.iter() {
             if !args.tools.is_empty() && !args.tools.contains(tool.as_str()) { continue }
-            for config in args.configs.iter() {
-                let start = std::time::Instant::now();
-                eprintln!();
-                status!(verbing, "{} | {} | {} crates", tool, config, crates.len());
-                let mut cmd = Command::new(tool.as_str());
-                cmd.env("PATH",                         &path);
-                cmd.env("CARGO_CONTAINER_COMMAND",      command);
-                cmd.env("CARGO_CONTAINER_CRATES_DIR",   format!(".container/crates/{}", tool));
-                cmd.env("CARGO_CONTAINER_ARCHES",       &arches);
-                cmd.env("CARGO_CONTAINER_CONFIGS",      config);
-                cmd.env("CARGO_CONTAINER_PACKAGES",     &crates_s);
-
-                cmd.stdin(Stdio::null());
-                cmd.stdout(Stdio::piped());
-                cmd.stderr(Stdio::inherit());
-
-                let prev_sudo_len = sudos.len();
-
-                let mut child = cmd.spawn().unwrap_or_else(|err| fatal!("`{}` {} failed: {}", tool, command, err));
-                let mut stdout = BufReader::new(child.stdout.take().unwrap());
-                let mut line = String::new();
-                loop {
-                    line.clear();
-                    match stdout.read_line(&mut line) {
-                        Ok(0) => break,                                                 // EOF
-                        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => break,   // EPIPE
-                        Err(err) => fatal!("error reading stdout from `{}` {}: {}", tool, command, err),
-                        Ok(_) => {},
-                    }
-                    let line = line.trim_end_matches("\n").trim_end_matches("\r");
-                    if let Some(cc) = line.strip_prefix("cargo-container:") {
-                        if let Some(sudo) = cc.strip_prefix("sudo=") {
-                            if prev_sudo_len == sudos.len() {
-                                sudos.push(format!("{} requested by {} {}", if cfg!(windows) { "::" } else { "#" }, tool, command));
-                            }
-                            sudos.push(sudo.into());
-                        } else if let Some(pkg) = cc.strip_prefix("apt-get-install=") {
-                            apt_packages.insert(String::from(pkg));
-                        } else if let Some(msg) = cc.strip_prefix("error=") {
-                            error!(code: tool, "{}", msg);
-                        } else if let Some(msg) = cc.strip_prefix("warning=") {
-                            warning!(code: tool, "{}", msg);
-                        } else if let Some(msg) = cc.strip_prefix("info=") {
-                            info!(code: tool, "{}", msg);
-                        } else {
-                            warning!("unrecognized directive: {:?}", line);
-                        }
-                    } else {
-                        // ...ignore?
-                        println!("{}\r", line); // XXX: this `\r` shouldn't be necessary, but there's fuckery going on
-                    }
-                }
+            if !capabilities::supports_host(meta, tool.as_str(), &path) { unsupported.insert(tool.as_str()); continue }
+            for config in configs.iter().copied() {
+                jobs.push(Job { tool: tool.as_str(), config, arches: arches.clone(), crates: crates.clone() });
+            }
+        }
+    }
 
-                if prev_sudo_len != sudos.len() {
-                    sudos.push(String::new());
-                }
+    if !unsupported.is_empty() {
+        status!("Skipping", "{} (not supported on {} hosts)", unsupported.into_iter().collect::<Vec<_>>().join(", "), std::env::consts::OS);
+    }
 
-                let status = child.wait().unwrap_or_else(|err| fatal!("`{}` {} failed: {}", tool, command, err));
-                match status.code() {
-                    Some(0x00) => builds = true, // success
-                    Some(0xEE) => std::process::exit(1), // errors
-                    Some(0x33) => builds = true, // warnings
-                    Some(0xC1) => {}, // command not implemented
-                    Some(0x91) => {}, // platform not implemented
+    for c in args.configs.iter().filter(|c| *c != "*") {
+        if !jobs.iter().any(|j| j.config == c) {
+            warning!("`--config {}` is not supported by any selected `[[build]]`", c);
+        }
+    }
 
-                    Some(n) => fatal!("`{}` {} failed (exit code {})", tool, command, n),
-                    None    => fatal!("`{}` {} failed (signal)", tool, command),
-                }
-                let stop = std::time::Instant::now();
-                status!("Finished", "{} | {} | {} crates in {:.2}s", tool, config, crates.len(), (stop-start).as_secs_f32());
-            }
+    for a in args.arches.iter().filter(|a| *a != "*") {
+        if !meta.builds.iter().any(|b| b.arches.is_empty() || b.arches.contains(a)) {
+            warning!("`--arch {}` is not supported by any `[[build]]`", a);
         }
     }
+
+    jobs
+}
+
+fn gen_then_fwd(meta: &ContainerToml, args: Vec<OsString>, command: &str, ok_none: bool, verbing: &str) {
+    let args = Args::from(args);
+    let (manifest, generate_failed) = prepare(meta, &args);
+    fwd(meta, &args, &manifest, &generate_failed, command, ok_none, verbing);
+}
+
+/// Forward `command` to every job's tool, returning the jobs whose tool doesn't implement it.
+///
+/// Jobs for tools in `generate_failed` aren't run, and count as errors.
+fn fwd<'a>(meta: &'a ContainerToml, args: &'a Args, manifest: &manifest::Packages, generate_failed: &BTreeSet<String>, command: &str, ok_none: bool, verbing: &str) -> Vec<Job<'a>> {
+    let jobs = jobs(meta, args);
+
+    let path = prepend_paths(Some(Path::new("bin").canonicalize().unwrap().cleanup()));
+    let passthrough = serde_json::to_string(&args.passthrough).unwrap();
+
+    let runner = invoke::Jobs::new(args.jobs);
+    let mut apt_packages = BTreeSet::new();
+    let mut job_sudos = vec![Vec::new(); jobs.len()];
+    let mut outcomes = vec![None; jobs.len()];
+    let mut counts = vec![Counts::default(); jobs.len()];
+    let mut builds = ok_none;
+
+    // Skip tool x config combinations the tools have told us they'd do nothing for
+    let mut skipped = Vec::new();
+    let mut not_implemented = BTreeSet::new();
+    let runnable = jobs.iter().enumerate().filter(|(i, job)| {
+        if generate_failed.contains(job.tool) {
+            outcomes[*i] = Some(exit::Outcome::Errors);
+            builds = true;
+            skipped.push(format!("{} | {} (generate failed)", job.tool, job.config));
+            return false;
+        }
+        let caps = capabilities::get(job.tool, &path);
+        match caps.as_ref().and_then(|caps| capabilities::unsupported(caps, command, Some(job.config), &job.arches)) {
+            None => true,
+            Some(reason) => {
+                if not_implemented_by(caps.as_ref(), command, None) { not_implemented.insert(*i); }
+                skipped.push(format!("{} | {} ({})", job.tool, job.config, reason));
+                false
+            },
+        }
+    }).map(|(i, _)| i).collect::<Vec<_>>();
+    if !skipped.is_empty() { status!("Skipping", "{}", skipped.join(", ")); }
+
+    runner.run(&runnable[..], |&i| {
+        let Job { tool, config, ref arches, ref crates } = jobs[i];
+        let start = std::time::Instant::now();
+        if !runner.parallel() { eprintln!(); }
+        status!(verbing, "{} | {} | {} crates", tool, config, crates.len());
+        let mut cmd = Command::new(tool);
+        cmd.env("PATH",                &path);
+        cmd.env(spec::env::COMMAND,    command);
+        cmd.env(spec::env::CRATES_DIR, format!(".container/crates/{}", tool));
+        cmd.env(spec::env::ARCHES,     arches);
+        invoke::configs(&mut cmd, meta, &[config]);
+        cmd.env(spec::env::PACKAGES,   crates.join(","));
+        manifest.set(&mut cmd, &format!("{}-{}-{}", tool, command, config), crates.iter().copied());
+        cmd.env(spec::env::ARGS,       &passthrough);
+        runner.configure(&mut cmd);
+
+        cmd.stdin(Stdio::null());
+        cmd.stderr(Stdio::inherit());
+
+        (invoke::tool(cmd, tool, command, runner.parallel(), capabilities::legacy(tool, &path)), start)
+    }, |_, &i, (invocation, start)| {
+        let Job { tool, config, ref crates, .. } = jobs[i];
+        if runner.parallel() {
+            eprintln!();
+            let _ = io::stderr().write_all(&invocation.output[..]);
+        }
+
+        let sudos = &mut job_sudos[i];
+        let counts = &mut counts[i];
+        for directive in invocation.directives {
+            match directive {
+                Err(err) => warning!(code: tool, "{}", err),
+                Ok(Directive::Sudo(sudo)) => {
+                    if sudos.is_empty() {
+                        sudos.push(format!("{} requested by {} {}", if cfg!(windows) { "::" } else { "#" }, tool, command));
+                    }
+                    sudos.push(sudo);
+                },
+                Ok(Directive::AptGetInstall(pkg)) => { apt_packages.insert(pkg); },
+                Ok(Directive::RerunIfChanged(_)) => warning!(code: tool, "ignoring directive: `rerun-if-changed` is only supported by `generate`"),
+                Ok(Directive::CargoConfig(_)) => warning!(code: tool, "ignoring directive: `cargo-config` is only supported by `generate`"),
+                Ok(Directive::Diagnostic(diag)) => {
+                    match diag.severity {
+                        directive::Severity::Error      => counts.errors += 1,
+                        directive::Severity::Warning    => counts.warnings += 1,
+                        directive::Severity::Info       => {},
+                    }
+                    diag.report(tool);
+                },
+            }
+        }
+
+        if !sudos.is_empty() {
+            sudos.push(String::new());
+        }
+
+        let mut outcome = exit::Outcome::from_status(invocation.status);
+        if args.deny_warnings && outcome < exit::Outcome::Errors && (outcome == exit::Outcome::Warnings || counts.warnings > 0) {
+            error!(code: tool, "{} failed: warnings denied by `--deny-warnings`", command);
+            outcome = exit::Outcome::Errors;
+        }
+        outcomes[i] = Some(outcome);
+        if not_implemented_by(None, command, Some(outcome)) { not_implemented.insert(i); }
+        if outcome.ran() { builds = true; }
+        match (outcome, invocation.status.code()) {
+            (exit::Outcome::Errors, _) if !args.keep_going => std::process::exit(1),
+            (exit::Outcome::Crash, Some(n)) if !args.keep_going => fatal!("`{}` {} failed (exit code {})", tool, command, n),
+            (exit::Outcome::Crash, None)    if !args.keep_going => fatal!("`{}` {} failed (signal)", tool, command),
+            (exit::Outcome::Crash, Some(n)) => error!("`{}` {} failed (exit code {})", tool, command, n),
+            (exit::Outcome::Crash, None)    => error!("`{}` {} failed (signal)", tool, command),
+            _ => {},
+        }
+        let stop = std::time::Instant::now();
+        status!("Finished", "{} | {} | {} crates in {:.2}s ({})", tool, config, crates.len(), (stop-start).as_secs_f32(), counts);
+    });
+    let total = counts.iter().fold(Counts::default(), |a, b| Counts { errors: a.errors + b.errors, warnings: a.warnings + b.warnings });
+    if jobs.len() > 1 { status!("Finished", "{} tool x config combinations ({})", jobs.len(), total); }
+    let mut sudos = job_sudos.concat();
     if !builds { fatal!("`{}`: matched no crate x tool combinations", command) }
 
     if !apt_packages.is_empty() {
         let mut install = String::from("apt-get install -y");
         for pkg in apt_packages.iter() {
-            install.push_str(" ");
+            install.push(' ');
             install.push_str(pkg.as_str());
         }
         sudos.push(comment("requested by cargo-container for apt-get-install directives"));
@@ -382,11 +622,141 @@ fn gen_then_fwd(meta: &ContainerToml, args: std::env::ArgsOs, command: &str, ok_
             }
         }
     }
+
+    if args.keep_going {
+        let worst = summary(&jobs[..], &outcomes[..], &counts[..]);
+        let code = worst.map_or(0, |o| o.exit_code());
+        if code != 0 { std::process::exit(code) }
+    }
+
+    jobs.into_iter().enumerate().filter(|(i, _)| not_implemented.contains(i)).map(|(_, job)| job).collect()
+}
+
+/// Print a table of how each tool x config combination went, returning the worst outcome.
+fn summary(jobs: &[Job], outcomes: &[Option<exit::Outcome>], counts: &[Counts]) -> Option<exit::Outcome> {
+    let tool_w   = jobs.iter().map(|j| j.tool.len()  ).chain(Some("tool".len()  )).max().unwrap();
+    let config_w = jobs.iter().map(|j| j.config.len()).chain(Some("config".len())).max().unwrap();
+
+    eprintln!();
+    status!("Summary", "{} tool x config combinations", jobs.len());
+    eprintln!("    {:tool_w$}  {:config_w$}  errors  warnings  result", "tool", "config", tool_w=tool_w, config_w=config_w);
+    for ((job, outcome), counts) in jobs.iter().zip(outcomes.iter()).zip(counts.iter()) {
+        let (color, result) = match outcome {
+            None                                => (90, "skipped"),
+            Some(o @ exit::Outcome::Success)    => (32, o.as_str()),
+            Some(o @ exit::Outcome::Warnings)   => (33, o.as_str()),
+            Some(o @ exit::Outcome::Errors)     => (31, o.as_str()),
+            Some(o @ exit::Outcome::Crash)      => (31, o.as_str()),
+            Some(o)                             => (90, o.as_str()),
+        };
+        eprintln!("    {:tool_w$}  {:config_w$}  {:6}  {:8}  \u{001B}[{}m{}\u{001B}[0m", job.tool, job.config, counts.errors, counts.warnings, color, result, tool_w=tool_w, config_w=config_w);
+    }
+    outcomes.iter().flatten().copied().max()
+}
+
+/// Run a single crate with a single tool, with stdin/stdout/stderr passed through, exiting with the program's exit code.
+fn run_crate(meta: &ContainerToml, args: Vec<OsString>) {
+    let args = Args::from(args);
+    let (manifest, generate_failed) = prepare(meta, &args);
+
+    if args.configs.len() > 1 { fatal!("`run` expects a single `--config`, but {} were specified", args.configs.len()) }
+    let path = prepend_paths(Some(Path::new("bin").canonicalize().unwrap().cleanup()));
+
+    // Without `--config`, run with the first config the `[[build]]` lists
+    let mut candidates = BTreeMap::new();
+    for job in jobs(meta, &args) {
+        if generate_failed.contains(job.tool) {
+            if args.tools.contains(job.tool) { warning!(code: job.tool, "skipping `--tool {}`: generate failed", job.tool) }
+            continue;
+        }
+        if let Some(reason) = capabilities::get(job.tool, &path).and_then(|caps| capabilities::unsupported(&caps, "run", Some(job.config), &job.arches)) {
+            if args.tools.contains(job.tool) { warning!(code: job.tool, "skipping `--tool {}`: {}", job.tool, reason) }
+            continue;
+        }
+        for krate in job.crates.iter().copied() {
+            candidates.entry((krate, job.tool)).or_insert((job.config, job.arches.clone()));
+        }
+    }
+    let candidates = candidates.into_iter().map(|((krate, tool), (config, arches))| (krate, tool, config, arches)).collect::<Vec<_>>();
+
+    let (krate, tool, config, arches) = match candidates.len() {
+        0 => fatal!("`run`: matched no crate x tool combinations"),
+        1 => candidates[0].clone(),
+        _ if io::stdin().is_terminal() => {
+            info!("multiple crate x tool combinations match - specify `--crate` and `--tool` to skip this prompt");
+            for (i, (krate, tool, _, _)) in candidates.iter().enumerate() {
+                eprintln!("    {:2})  {} | {}", i+1, krate, tool);
+            }
+            loop {
+                eprint!("which would you like to run? (1-{}) ", candidates.len());
+                let mut line = String::new();
+                if io::stdin().read_line(&mut line).unwrap_or(0) == 0 { fatal!("`run`: no crate x tool combination selected") }
+                match line.trim().parse::<usize>() {
+                    Ok(n) if 1 <= n && n <= candidates.len() => break candidates[n-1].clone(),
+                    _ => warning!("expected a number from 1 to {}", candidates.len()),
+                }
+            }
+        },
+        n => fatal!("`run`: {} crate x tool combinations match - specify a single `--crate` and `--tool`", n),
+    };
+
+    eprintln!();
+    status!("Running", "{} | {} | {}", krate, tool, config);
+    let mut cmd = Command::new(tool);
+    cmd.env("PATH",                &path);
+    cmd.env(spec::env::COMMAND,    "run");
+    cmd.env(spec::env::CRATES_DIR, format!(".container/crates/{}", tool));
+    cmd.env(spec::env::ARCHES,     &arches);
+    invoke::configs(&mut cmd, meta, &[config]);
+    cmd.env(spec::env::PACKAGES,   krate);
+    manifest.set(&mut cmd, &format!("{}-run", tool), std::iter::once(krate));
+    cmd.env(spec::env::ARGS,       serde_json::to_string(&args.passthrough).unwrap());
+    let invocation = invoke::interactive(cmd, tool, "run");
+
+    for directive in invocation.directives {
+        match directive {
+            Ok(Directive::Diagnostic(diag)) => diag.report(tool),
+            Ok(_)   => warning!(code: tool, "ignoring directive: only diagnostics are supported by `run`"),
+            Err(err) => warning!(code: tool, "{}", err),
+        }
+    }
+
+    // Tools exit with the program's exit code, so only "not implemented" codes are interpreted here
+    match invocation.status.code() {
+        Some(exit::COMMAND_NOT_IMPLEMENTED)     => fatal!("`{}` doesn't implement `run`", tool),
+        Some(exit::PLATFORM_NOT_IMPLEMENTED)    => fatal!("`{}` can't `run` on this platform", tool),
+        Some(0)                                 => {},
+        Some(code)                              => std::process::exit(code),
+        None                                    => fatal!("`{}` run terminated by signal", tool),
+    }
+}
+
+struct Job<'a> {
+    tool:   &'a str,
+    config: &'a str,
+    arches: String,
+    crates: Vec<&'a str>,
+}
+
+/// Error and warning diagnostics reported by a tool
+#[derive(Clone, Copy, Default)]
+struct Counts {
+    errors:     usize,
+    warnings:   usize,
+}
+
+impl std::fmt::Display for Counts {
+    fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(fmt, "{} error{}, {} warning{}",
+            self.errors,   if self.errors   == 1 { "" } else { "s" },
+            self.warnings, if self.warnings == 1 { "" } else { "s" },
+        )
+    }
 }
 
 fn local_install(meta: &ContainerToml, args: &Args) {
     if args.skip_install { return }
-    if meta.local_install.is_empty() { return }
+    if meta.host_local_install().is_empty() { return }
     cargo_local_install::run_from_strs(vec![
         OsStr::new("--no-path-warning"),
         //OsStr::new("--root"), meta.root_directory().join(".container").as_os_str(),
@@ -401,3 +771,12 @@ fn is_comment(line: &str) -> bool {
 fn comment(c: &str) -> String {
     format!("{} {}", if cfg!(windows) { "::" } else { "#" }, c)
 }
+
+#[test] fn test_not_implemented_by() {
+    let caps = capabilities::Capabilities::default().commands(&["build", "check", "clippy"]);
+    assert!( not_implemented_by(Some(&caps), "test",    None));
+    assert!(!not_implemented_by(Some(&caps), "clippy",  None));
+    assert!(!not_implemented_by(None,        "clippy",  Some(exit::Outcome::PlatformNotImplemented))); // e.g. opendingux's xargo sysroot doesn't extend to clippy
+    assert!(!not_implemented_by(None,        "clippy",  Some(exit::Outcome::Errors)));
+    assert!( not_implemented_by(None,        "clippy",  Some(exit::Outcome::CommandNotImplemented)));
+}
@@ -0,0 +1,238 @@
+//! Sanity checks for `Container.toml`, reported against the lines they came from.
+
+use crate::*;
+use container_toml::{Build, Config, Root};
+use cargo_container_sdk::spec::env;
+
+use mmrbi::*;
+
+use std::path::Path;
+
+
+
+/// Check that `meta`'s crates and tools exist, and warn about unknown keys.  Exits if any errors were found.
+pub fn container_toml(meta: &ContainerToml) {
+    let path = meta.manifest_path();
+    let text = std::fs::read_to_string(path).unwrap_or_else(|err| fatal!("unable to read `{}`: {}", path.display(), err));
+    let raw : toml::Value = toml::from_str(&text).unwrap_or_else(|err| fatal!("unable to parse `{}`: {}", path.display(), err));
+    let lines = Lines(&text);
+    let mut errors = 0;
+
+    for key in raw.as_table().into_iter().flat_map(|t| t.keys()) {
+        if Root::KEYS.contains(&key.as_str()) { continue }
+        let (line, col) = lines.key(key, 0);
+        warning!(at: path, line: line, col: col, "unknown key `{}`{}", key, did_you_mean(key, Root::KEYS.iter().copied()));
+    }
+
+    for (i, build) in raw.get("build").and_then(|b| b.as_array()).into_iter().flatten().enumerate() {
+        let start = lines.build(i);
+        for key in build.as_table().into_iter().flat_map(|t| t.keys()) {
+            if Build::KEYS.contains(&key.as_str()) { continue }
+            let (line, col) = lines.key(key, start);
+            warning!(at: path, line: line, col: col, "unknown key `{}` in `[[build]]`{}", key, did_you_mean(key, Build::KEYS.iter().copied()));
+        }
+    }
+
+    for (name, config) in raw.get("config").and_then(|c| c.as_table()).into_iter().flatten() {
+        let start = lines.table(&format!("config.{}", name));
+        for key in config.as_table().into_iter().flat_map(|t| t.keys()) {
+            if Config::KEYS.contains(&key.as_str()) { continue }
+            let (line, col) = lines.key(key, start);
+            warning!(at: path, line: line, col: col, "unknown key `{}` in `[config.{}]`{}", key, name, did_you_mean(key, Config::KEYS.iter().copied()));
+        }
+    }
+
+    for (tool, install) in meta.local_install.iter() {
+        match install.get("hosts") {
+            None => {},
+            Some(toml::Value::Array(hosts)) if hosts.iter().all(|h| h.is_str()) => {},
+            Some(_) => {
+                errors += 1;
+                let (line, col) = lines.key(tool, lines.table("local-install"));
+                error!(at: path, line: line, col: col, "`[local-install] {}` `hosts` must be an array of strings, e.g. `[\"windows\", \"linux\"]`", tool);
+            },
+        }
+    }
+
+    let profiles = ["dev", "release", "test", "bench"].iter().copied().chain(meta.profile.keys().map(|p| p.as_str())).collect::<Vec<_>>();
+    for (name, config) in meta.config.iter() {
+        if profiles.contains(&config.profile()) { continue }
+        errors += 1;
+        let (line, col) = lines.key("profile", lines.table(&format!("config.{}", name)));
+        error!(at: path, line: line, col: col, "`[config.{}]` uses profile `{}`, which isn't built-in or defined in `[profile]`{}", name, config.profile(), did_you_mean(config.profile(), profiles.iter().copied()));
+    }
+
+    let configs = meta.configs();
+    let mut env_names = std::collections::BTreeMap::new();
+    for config in configs.iter().copied() {
+        let other = match env_names.insert(env::config_name(config), config) { Some(other) => other, None => continue };
+        errors += 1;
+        error!(at: path, line: lines.table(&format!("config.{}", config)), col: 1, "`[config.{}]` and `{}` would both be passed to tools as `CARGO_CONTAINER_CONFIG_{}_*`", config, other, env::config_name(config));
+    }
+
+    for (i, build) in meta.builds.iter().enumerate() {
+        for config in build.configs.iter() {
+            if meta.config(config).is_some() { continue }
+            errors += 1;
+            let (line, col) = lines.string(config, lines.build(i));
+            error!(at: path, line: line, col: col, "`{}` is not `debug`, `release`, or a `[config.{}]`{}", config, config, did_you_mean(config, configs.iter().copied()));
+        }
+    }
+
+    let packages = meta.resolve_packages().unwrap_or_else(|err| fatal!("unable to resolve packages: {}", err));
+    let paths = prepend_paths(Some(meta.root_directory().join("bin")));
+    let bin = std::fs::read_dir(meta.root_directory().join("bin")).into_iter().flatten().flatten()
+        .filter_map(|e| Some(Path::new(&e.file_name()).file_stem()?.to_str()?.to_string())).collect::<Vec<_>>();
+    for (i, build) in meta.builds.iter().enumerate() {
+        let start = lines.build(i);
+        for krate in build.crates.iter() {
+            if packages.contains_key(krate) { continue }
+            errors += 1;
+            let (line, col) = lines.string(krate.as_str(), start);
+            error!(at: path, line: line, col: col, "`{}` is not a package in this workspace{}", krate, did_you_mean(krate.as_str(), packages.keys().map(|p| p.as_str())));
+        }
+        for tool in build.tools.iter() {
+            if meta.local_install.contains_key(tool.as_str()) || find_on_path(&paths, tool.as_str()).is_some() { continue }
+            errors += 1;
+            let (line, col) = lines.string(tool.as_str(), start);
+            error!(at: path, line: line, col: col, "`{}` is not a `[local-install]` tool, and wasn't found on %PATH%{}", tool, did_you_mean(tool.as_str(), meta.local_install.keys().map(|k| k.as_str()).chain(bin.iter().map(|b| b.as_str()))));
+        }
+    }
+
+    let mut checked = std::collections::BTreeSet::new();
+    for krate in meta.builds.iter().flat_map(|b| b.crates.iter()) {
+        if !checked.insert(krate) { continue }
+        if let Some(package) = packages.get(krate) { errors += package_metadata(package) }
+    }
+
+    if errors > 0 { std::process::exit(1) }
+}
+
+/// Check a wrapped crate's `[package.metadata.container]`, returning the number of errors
+fn package_metadata(package: &mmrbi::cargo::metadata::Package) -> usize {
+    let container = match package.package.metadata.get("container") {
+        None => return 0,
+        Some(container) => container,
+    };
+    let path = package.manifest_path();
+    let text = std::fs::read_to_string(path).unwrap_or_default();
+    let lines = Lines(&text);
+    let start = lines.table("package.metadata.container");
+    let mut errors = 0;
+
+    let container = match container.as_table() {
+        Some(container) => container,
+        None => {
+            let (line, col) = lines.key("container", 0);
+            error!(at: path, line: line, col: col, "`[package.metadata] container` must be a table");
+            return 1;
+        },
+    };
+
+    for (key, value) in container.iter() {
+        let (line, col) = lines.key(key, start);
+        let problem = match (key.as_str(), value) {
+            ("name",        toml::Value::String(s)) if s.is_empty() => Some("must not be empty".to_string()),
+            ("name",        toml::Value::String(_)) => None,
+            ("icon",        toml::Value::String(s)) if !package.directory().join(s).is_file() => Some(format!("`{}` not found", package.directory().join(s).display())),
+            ("icon",        toml::Value::String(_)) => None,
+            ("id",          toml::Value::String(s)) if !is_app_id(s) => Some(format!("`{}` is not a reverse-DNS id like `com.example.app`", s)),
+            ("id",          toml::Value::String(_)) => None,
+            ("category",    toml::Value::String(s)) if !manifest::APP_CATEGORIES.contains(&s.as_str()) => Some(format!("`{}` is not one of {}{}", s, manifest::APP_CATEGORIES.join(", "), did_you_mean(s, manifest::APP_CATEGORIES.iter().copied()))),
+            ("category",    toml::Value::String(_)) => None,
+            ("terminal",    toml::Value::Boolean(_)) => None,
+            ("terminal",    _) => Some("must be `true` or `false`".to_string()),
+            (key, _) if manifest::APP_KEYS.contains(&key) => Some("must be a string".to_string()),
+            (key, _) => {
+                warning!(at: path, line: line, col: col, "unknown key `{}` in `[package.metadata.container]`{}", key, did_you_mean(key, manifest::APP_KEYS.iter().copied()));
+                None
+            },
+        };
+        if let Some(problem) = problem {
+            errors += 1;
+            error!(at: path, line: line, col: col, "`[package.metadata.container] {}` {}", key, problem);
+        }
+    }
+    errors
+}
+
+/// `true` if `id` looks like `com.example.app`
+fn is_app_id(id: &str) -> bool {
+    id.split('.').count() >= 2 && id.split('.').all(|part| part.starts_with(|c: char| c.is_ascii_alphabetic()) && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+}
+
+/// `", did you mean `...`?"` if any of `candidates` is close enough to `name`
+fn did_you_mean<'c>(name: &str, candidates: impl Iterator<Item = &'c str>) -> String {
+    let best = candidates.map(|c| (levenshtein(name, c), c)).min();
+    match best {
+        Some((d, c)) if d <= name.len().div_ceil(3) => format!(", did you mean `{}`?", c),
+        _ => String::new(),
+    }
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut prev = (0 ..= b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            row.push((prev[j] + (ca != *cb) as usize).min(prev[j+1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+    prev[b.len()]
+}
+
+/// Best-effort (1-based) line/column lookup in the raw TOML text - `toml` doesn't preserve spans.
+struct Lines<'t>(&'t str);
+
+impl Lines<'_> {
+    /// The line of the `i`th `[[build]]` header (or 0 if not found)
+    fn build(&self, i: usize) -> usize {
+        self.0.lines().enumerate().filter(|(_, l)| l.trim_start().starts_with("[[build]]")).nth(i).map_or(0, |(n, _)| n + 1)
+    }
+
+    /// The line of the `[header]` (or 0 if not found)
+    fn table(&self, header: &str) -> usize {
+        let header = format!("[{}]", header);
+        self.0.lines().enumerate().find(|(_, l)| l.trim_start().starts_with(&header)).map_or(0, |(n, _)| n + 1)
+    }
+
+    /// The first line/column at or after line `start` defining `key`
+    fn key(&self, key: &str, start: usize) -> (usize, usize) {
+        self.find(start, |l| {
+            let col = l.find(key)?;
+            let before = l[..col].trim_start().trim_start_matches('[');
+            let after = l[col+key.len()..].trim_start();
+            if before.is_empty() && after.starts_with(['=', '.', ']']) { Some(col) } else { None }
+        })
+    }
+
+    /// The first line/column at or after line `start` containing `"s"`
+    fn string(&self, s: &str, start: usize) -> (usize, usize) {
+        let quoted = format!("{:?}", s);
+        self.find(start, |l| l.find(&quoted))
+    }
+
+    fn find(&self, start: usize, f: impl Fn(&str) -> Option<usize>) -> (usize, usize) {
+        self.0.lines().enumerate().skip(start.saturating_sub(1)).find_map(|(n, l)| Some((n + 1, f(l)? + 1))).unwrap_or((start, 1))
+    }
+}
+
+
+
+#[test] fn test_levenshtein() {
+    assert_eq!(levenshtein("alpha", "alpha"), 0);
+    assert_eq!(levenshtein("alpah", "alpha"), 2);
+    assert_eq!(levenshtein("platform-consle", "platform-console"), 1);
+    assert_eq!(did_you_mean("alpah", ["alpha", "beta"].iter().copied()), ", did you mean `alpha`?");
+    assert_eq!(did_you_mean("gamma", ["alpha", "beta"].iter().copied()), "");
+}
+
+#[test] fn test_is_app_id() {
+    assert!( is_app_id("com.example.alpha"));
+    assert!( is_app_id("com.example.alpha-centauri_2"));
+    assert!(!is_app_id("alpha"));
+    assert!(!is_app_id("com..alpha"));
+    assert!(!is_app_id("com.2example"));
+}